@@ -0,0 +1,86 @@
+//! Tokenises and parses a multi-megabyte SQL script, reporting the mean time and throughput.
+//!
+//! Run with `cargo bench`, or `cargo bench -- <file>` to use a SQL file instead of the generated
+//! script.
+
+use std::{
+    fmt::Write,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use sqlp2::{dialect::GenericDialect, Parser, StatementStream, Tokeniser};
+
+const TARGET_SIZE: usize = 8 * 1024 * 1024;
+const MIN_ITERATIONS: u32 = 5;
+const MIN_DURATION: Duration = Duration::from_secs(2);
+
+// A migration dump style script, mostly multi-row inserts with some queries mixed in
+fn generate() -> String {
+    let mut sql = String::with_capacity(TARGET_SIZE);
+    let mut table = 0;
+    while sql.len() < TARGET_SIZE {
+        writeln!(
+            sql,
+            "-- table {table}\n\
+             CREATE TABLE \"table_{table}\" (id INT, name VARCHAR(255), email VARCHAR(255), age INT);"
+        )
+        .unwrap();
+
+        for batch in 0..10 {
+            write!(sql, "INSERT INTO table_{table} (id, name, email, age) VALUES ").unwrap();
+            for row in 0..100 {
+                let id = batch * 100 + row;
+                let sep = if row == 0 { "" } else { ", " };
+                write!(sql, "{sep}({id}, 'user ''{id}''', 'user{id}@example.com', {})", id % 90)
+                    .unwrap();
+            }
+            writeln!(sql, ";").unwrap();
+        }
+
+        writeln!(
+            sql,
+            "SELECT t.name, count(*) AS total FROM table_{table} AS t \
+             LEFT JOIN s1.accounts AS a ON t.id = a.user_id \
+             WHERE t.age BETWEEN 18 AND 65 AND a.email IS NOT NULL \
+             GROUP BY t.name HAVING count(*) > 1 ORDER BY total DESC LIMIT 10;\n\
+             UPDATE table_{table} SET age = age + 1, name = 'renamed' WHERE id IN (1, 2, 3);\n\
+             DELETE FROM table_{table} WHERE age < 18 OR name = '';"
+        )
+        .unwrap();
+
+        table += 1;
+    }
+
+    sql
+}
+
+fn bench<T>(name: &str, bytes: usize, mut f: impl FnMut() -> T) {
+    black_box(f());
+
+    let (mut iterations, start) = (0, Instant::now());
+    while iterations < MIN_ITERATIONS || start.elapsed() < MIN_DURATION {
+        black_box(f());
+        iterations += 1;
+    }
+
+    let mean = start.elapsed() / iterations;
+    let throughput = bytes as f64 / (1024.0 * 1024.0) / mean.as_secs_f64();
+    println!("{name:<10} {mean:>12.2?}/iter {throughput:>10.2} MiB/s ({iterations} iterations)");
+}
+
+fn main() {
+    let sql = match std::env::args().skip(1).find(|arg| !arg.starts_with('-')) {
+        Some(path) => std::fs::read_to_string(path).expect("failed to read SQL file"),
+        None => generate(),
+    };
+    println!("{:.2} MiB of SQL", sql.len() as f64 / (1024.0 * 1024.0));
+
+    bench("tokenise", sql.len(), || {
+        Tokeniser::new(&sql, &GenericDialect).collect_with_span().unwrap()
+    });
+    bench("parse", sql.len(), || Parser::new(&sql, &GenericDialect).unwrap().parse().unwrap());
+    bench("stream", sql.len(), || {
+        StatementStream::new(sql.as_bytes(), &GenericDialect).map(Result::unwrap).count()
+    });
+}
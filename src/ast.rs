@@ -0,0 +1,1640 @@
+use std::{
+    cell::Cell,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    dialect::{self, Dialect, GenericDialect},
+    tokeniser::{Keyword, QuoteStyle, Span},
+};
+
+/// A single parsed SQL statement
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+#[non_exhaustive]
+pub enum StatementKind {
+    Select(Select),
+    Insert(Insert),
+    Update(Update),
+    Delete(Delete),
+    Create(Create),
+    CreateIndex(CreateIndex),
+    CreateView(CreateView),
+    AlterTable(AlterTable),
+    Drop(Drop),
+    Truncate(Truncate),
+}
+
+/// A literal value
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum Value {
+    /// A number as written in the source, unless the parser is typing numbers
+    Number(String),
+    Int(i64),
+    Float(f64),
+    Decimal(Decimal),
+    String(String),
+    /// The hex digits of an `X'...'` literal
+    HexString(String),
+    Bool(bool),
+    Null,
+}
+
+/// An exact decimal number, `mantissa * 10^-scale`, without trailing zeros after the decimal
+/// point
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Decimal {
+    pub mantissa: i128,
+    pub scale: u32,
+}
+
+impl Decimal {
+    /// The largest scale the parser produces
+    pub const MAX_SCALE: u32 = 38;
+
+    /// Normalises away trailing zeros after the decimal point
+    pub fn new(mut mantissa: i128, mut scale: u32) -> Self {
+        if mantissa == 0 {
+            return Self { mantissa, scale: 0 };
+        }
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+
+        Self { mantissa, scale }
+    }
+}
+
+/// A binary operator
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum Op {
+    Eq,
+    Neq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Modulo,
+    Concat,
+}
+
+/// A prefix operator
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum UnaryOp {
+    Plus,
+    Minus,
+    Not,
+}
+
+/// A possibly qualified identifier, e.g. `col` or `t1.col`
+#[derive(PartialEq, Debug, Clone)]
+pub enum Ident {
+    Single(Name),
+    Compound(Vec<Name>),
+}
+
+/// A single identifier, and whether it was quoted in the source. A quoted name is displayed
+/// quoted, so it keeps its case in dialects that fold unquoted identifiers, and an unquoted name
+/// is displayed bare unless it's a reserved keyword in the dialect
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Name {
+    pub value: String,
+    pub quoted: bool,
+}
+
+impl Name {
+    /// An unquoted name
+    pub fn new(value: impl Into<String>) -> Self {
+        Self { value: value.into(), quoted: false }
+    }
+
+    /// A name that was quoted in the source
+    pub fn quoted(value: impl Into<String>) -> Self {
+        Self { value: value.into(), quoted: true }
+    }
+}
+
+impl From<&str> for Name {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for Name {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+/// An expression and the span of source it was parsed from
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum ExprKind {
+    Ident(Ident),
+    Wildcard,
+    QualifiedWildcard(Vec<Name>),
+    Value(Value),
+    IsNull(Box<Expr>),
+    IsNotNull(Box<Expr>),
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    Between {
+        expr: Box<Expr>,
+        negated: bool,
+        low: Box<Expr>,
+        high: Box<Expr>,
+    },
+    BinaryOp {
+        left: Box<Expr>,
+        op: Op,
+        right: Box<Expr>,
+    },
+    SubQuery(Box<Query>),
+    GroupingSets(Vec<Vec<Expr>>),
+    Rollup(Vec<Vec<Expr>>),
+    Cube(Vec<Vec<Expr>>),
+    Function {
+        name: Ident,
+        args: Vec<Expr>,
+        distinct: bool,
+        filter: Option<Box<Expr>>,
+    },
+    UnaryOp {
+        op: UnaryOp,
+        expr: Box<Expr>,
+    },
+    /// `DEFAULT` in an INSERT row
+    Default,
+}
+
+/// The body of a SELECT, without ORDER BY, LIMIT and OFFSET
+#[derive(PartialEq, Debug, Clone)]
+pub struct Query {
+    pub projection: Vec<SelectItem>,
+    pub from: FromTable,
+    pub joins: Vec<Join>,
+    pub filter: Option<Expr>,
+    pub group: Vec<Expr>,
+    pub having: Option<Expr>,
+}
+
+/// A table or derived table in a FROM clause or join
+#[derive(Debug, Clone)]
+pub struct FromTable {
+    pub kind: FromTableKind,
+    pub span: Span,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum FromTableKind {
+    Table { name: Ident, alias: Option<Name> },
+    Derived { lateral: bool, query: Box<Query>, alias: Option<Name> },
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum JoinConstraint {
+    On(Expr),
+    Using(Vec<Name>),
+    Natural,
+    None,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+    Full,
+    Cross,
+    Comma, // FROM t1, t2
+}
+
+/// A joined table, spanning from the join keywords to the end of its constraint
+#[derive(Debug, Clone)]
+pub struct Join {
+    pub from: FromTable,
+    pub ty: JoinType,
+    pub constraint: JoinConstraint,
+    pub span: Span,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct OrderByExpr {
+    pub expr: Expr,
+    pub desc: bool,                // Default is false/ASC
+    pub nulls_first: Option<bool>, // None if NULLS FIRST/LAST isn't specified
+}
+
+/// An item in the SELECT projection
+#[derive(Debug, Clone)]
+pub struct SelectItem {
+    pub kind: SelectItemKind,
+    pub span: Span,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum SelectItemKind {
+    Expr(Expr),
+    AliasedExpr { expr: Expr, alias: Name },
+    QualifiedWildcard(Vec<Name>),
+    Wildcard,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Select {
+    pub body: Query,
+    pub order: Vec<OrderByExpr>,
+    pub limit: Option<Expr>,
+    pub offset: Option<Expr>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Insert {
+    pub table: Ident,
+    pub columns: Vec<Name>, // Empty if the columns aren't specified
+    pub source: InsertSource,
+    pub on_conflict: Option<OnConflict>,
+    pub returning: Vec<SelectItem>,
+}
+
+/// The rows inserted by an INSERT
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum InsertSource {
+    /// `VALUES (...), (...)`, a row can contain [`ExprKind::Default`]
+    Values(Vec<Vec<Expr>>),
+    Select(Box<Select>),
+    DefaultValues,
+}
+
+/// What an INSERT does with a row that conflicts with an existing one
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum OnConflict {
+    /// Postgres and SQLite `ON CONFLICT [(columns)] DO ...`
+    OnConflict { columns: Vec<Name>, action: ConflictAction },
+    /// MySQL `ON DUPLICATE KEY UPDATE ...`
+    DuplicateKeyUpdate(Vec<Set>),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum ConflictAction {
+    DoNothing,
+    DoUpdate { set: Vec<Set>, filter: Option<Expr> },
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Update {
+    pub table: Ident,
+    pub set: Vec<Set>,
+    pub filter: Option<Expr>,
+    pub returning: Vec<SelectItem>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Set {
+    pub column: Ident,
+    pub expr: Expr,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Delete {
+    pub table: Ident,
+    pub filter: Option<Expr>,
+    pub returning: Vec<SelectItem>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Create {
+    pub name: Ident,
+    pub if_not_exists: bool,
+    pub columns: Vec<ColumnDef>,
+    pub constraints: Vec<TableConstraint>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum ColumnType {
+    SmallInt,
+    /// `INT` or `INTEGER`
+    Int,
+    BigInt,
+    /// `BOOLEAN` or `BOOL`
+    Boolean,
+    Real,
+    /// `DOUBLE` or `DOUBLE PRECISION`
+    Double,
+    /// `DECIMAL` or `NUMERIC`, with an optional precision and scale
+    Decimal {
+        precision: Option<u32>,
+        scale: Option<u32>,
+    },
+    Char(Option<u32>),
+    Varchar(u32),
+    Text,
+    Date,
+    Time {
+        with_time_zone: bool,
+    },
+    Timestamp {
+        with_time_zone: bool,
+    },
+    Blob,
+    Bytea,
+    Json,
+    /// `ty[]` or `ty ARRAY`
+    Array(Box<ColumnType>),
+    /// Any other type, with its arguments as SQL text, e.g. `geometry(point, 4326)`
+    Custom(Name, Vec<String>),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct ColumnDef {
+    pub ty: ColumnType,
+    pub name: Name,
+    pub constraints: Vec<ColumnConstraint>,
+}
+
+/// A constraint following a column's type, optionally named with `CONSTRAINT name`
+#[derive(PartialEq, Debug, Clone)]
+pub struct ColumnConstraint {
+    pub name: Option<Name>,
+    pub kind: ColumnConstraintKind,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum ColumnConstraintKind {
+    Null,
+    NotNull,
+    Default(Expr),
+    PrimaryKey,
+    Unique,
+    Check(Expr),
+    References(References),
+}
+
+/// A constraint in the column list of a CREATE TABLE, optionally named with `CONSTRAINT name`
+#[derive(PartialEq, Debug, Clone)]
+pub struct TableConstraint {
+    pub name: Option<Name>,
+    pub kind: TableConstraintKind,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum TableConstraintKind {
+    PrimaryKey(Vec<Name>),
+    Unique(Vec<Name>),
+    ForeignKey { columns: Vec<Name>, references: References },
+    Check(Expr),
+}
+
+/// The target of a foreign key, `REFERENCES table (columns)`. The columns are empty if they
+/// aren't specified, referring to the primary key
+#[derive(PartialEq, Debug, Clone)]
+pub struct References {
+    pub table: Ident,
+    pub columns: Vec<Name>,
+    pub on_delete: Option<ReferentialAction>,
+    pub on_update: Option<ReferentialAction>,
+}
+
+/// What happens to referencing rows when the referenced row is deleted or updated
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ReferentialAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+/// `CREATE INDEX`, a partial index if it has a filter
+#[derive(PartialEq, Debug, Clone)]
+pub struct CreateIndex {
+    pub name: Name,
+    pub unique: bool,
+    pub if_not_exists: bool,
+    pub table: Ident,
+    pub columns: Vec<OrderByExpr>,
+    pub filter: Option<Expr>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct CreateView {
+    pub name: Ident,
+    pub or_replace: bool,
+    pub columns: Vec<Name>, // Empty if the columns are named by the query
+    pub query: Select,
+}
+
+/// `ALTER TABLE`, applying each operation in order
+#[derive(PartialEq, Debug, Clone)]
+pub struct AlterTable {
+    pub name: Ident,
+    pub if_exists: bool,
+    pub operations: Vec<AlterTableOperation>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum AlterTableOperation {
+    AddColumn { if_not_exists: bool, column: ColumnDef },
+    DropColumn { name: Name, if_exists: bool, cascade: bool },
+    RenameColumn { from: Name, to: Name },
+    AlterColumn { name: Name, op: AlterColumnOperation },
+    AddConstraint(TableConstraint),
+    DropConstraint { name: Name, if_exists: bool, cascade: bool },
+    RenameTo(Ident),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum AlterColumnOperation {
+    SetNotNull,
+    DropNotNull,
+    SetDefault(Expr),
+    DropDefault,
+    /// `SET DATA TYPE` or `TYPE`
+    SetType(ColumnType),
+}
+
+/// `DROP TABLE`, `DROP INDEX` or `DROP VIEW`
+#[derive(PartialEq, Debug, Clone)]
+pub struct Drop {
+    pub object: ObjectType,
+    pub if_exists: bool,
+    pub names: Vec<Ident>,
+    pub cascade: bool, // Default is false/RESTRICT
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ObjectType {
+    Table,
+    Index,
+    View,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Truncate {
+    pub tables: Vec<Ident>,
+    pub cascade: bool, // Default is false/RESTRICT
+}
+
+// Spans are ignored when comparing nodes, so the same SQL laid out differently compares equal
+macro_rules! spanned {
+    ($($node:ident($kind:ident)),*) => {
+        $(
+            impl $node {
+                pub fn new(kind: $kind, span: Span) -> Self {
+                    Self { kind, span }
+                }
+            }
+
+            impl From<$kind> for $node {
+                fn from(kind: $kind) -> Self {
+                    Self { kind, span: Span::default() }
+                }
+            }
+
+            impl PartialEq for $node {
+                fn eq(&self, other: &Self) -> bool {
+                    self.kind == other.kind
+                }
+            }
+        )*
+    };
+}
+
+spanned! {
+    Statement(StatementKind),
+    Expr(ExprKind),
+    FromTable(FromTableKind),
+    SelectItem(SelectItemKind)
+}
+
+impl PartialEq for Join {
+    fn eq(&self, other: &Self) -> bool {
+        self.from == other.from && self.ty == other.ty && self.constraint == other.constraint
+    }
+}
+
+impl Op {
+    /// The binding power of the operator, matching `Parser::next_prec`
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Op::Multiply | Op::Divide | Op::Modulo => 40,
+            Op::Plus | Op::Minus => 30,
+            Op::Concat => 25,
+            Op::Eq | Op::Neq | Op::Lt | Op::Le | Op::Gt | Op::Ge => 20,
+            Op::And => 10,
+            Op::Or => 5,
+        }
+    }
+}
+
+impl Statement {
+    /// Displays the statement quoted for `dialect`, see [`DialectDisplay`]
+    pub fn display<'a>(&'a self, dialect: &dyn Dialect) -> DialectDisplay<'a, Self> {
+        DialectDisplay::new(self, dialect)
+    }
+}
+
+impl Expr {
+    /// Displays the expression quoted for `dialect`, see [`DialectDisplay`]
+    pub fn display<'a>(&'a self, dialect: &dyn Dialect) -> DialectDisplay<'a, Self> {
+        DialectDisplay::new(self, dialect)
+    }
+
+    /// The binding power of the expression's outermost operator, atoms bind tightest
+    pub fn precedence(&self) -> u8 {
+        match &self.kind {
+            ExprKind::BinaryOp { op, .. } => op.precedence(),
+            ExprKind::IsNull(_) | ExprKind::IsNotNull(_) => 17,
+            ExprKind::InList { .. } | ExprKind::Between { .. } => 20,
+            ExprKind::UnaryOp { op: UnaryOp::Not, .. } => 15,
+            ExprKind::UnaryOp { .. } => 50,
+            // Negative numbers bind like a unary minus, so e.g. `-(-1)` isn't written as `--1`
+            ExprKind::Value(Value::Int(n)) if *n < 0 => 50,
+            ExprKind::Value(Value::Float(n)) if n.is_sign_negative() => 50,
+            ExprKind::Value(Value::Decimal(n)) if n.mantissa < 0 => 50,
+            _ => u8::MAX,
+        }
+    }
+}
+
+// Writes `items` separated by `sep`
+struct Separated<'a, T>(&'a [T], &'static str);
+
+impl<T: Display> Display for Separated<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.1)?;
+            }
+            write!(f, "{item}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn comma_separated<T: Display>(items: &[T]) -> Separated<'_, T> {
+    Separated(items, ", ")
+}
+
+thread_local! {
+    // The style nodes are displayed in, only changed while a `DialectDisplay` is written
+    static DISPLAY_STYLE: Cell<DisplayStyle> = Cell::new(DisplayStyle::new(&GenericDialect));
+}
+
+/// Displays a node as SQL that tokenises back to the same node in a dialect, quoting identifiers
+/// and escaping strings with its [`QuoteStyle`], and quoting names that are reserved keywords in
+/// it. Displaying a node directly displays it for [`GenericDialect`].
+pub struct DialectDisplay<'a, T> {
+    node: &'a T,
+    style: DisplayStyle,
+}
+
+impl<'a, T> DialectDisplay<'a, T> {
+    fn new(node: &'a T, dialect: &dyn Dialect) -> Self {
+        Self { node, style: DisplayStyle::new(dialect) }
+    }
+}
+
+impl<T: Display> Display for DialectDisplay<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let _guard = DisplayStyleGuard(DISPLAY_STYLE.replace(self.style));
+        write!(f, "{}", self.node)
+    }
+}
+
+// What's needed from a dialect to display nodes for it, copied out so it can be kept in a
+// thread local
+#[derive(Clone, Copy)]
+struct DisplayStyle {
+    quote_style: QuoteStyle,
+    // A bit for each keyword, set if it's reserved
+    reserved: u128,
+}
+
+const _: () = assert!(Keyword::ALL.len() <= 128);
+
+impl DisplayStyle {
+    fn new(dialect: &dyn Dialect) -> Self {
+        let reserved = Keyword::ALL
+            .iter()
+            .filter(|&&kw| dialect::is_reserved(dialect, kw))
+            .fold(0, |reserved, &kw| reserved | 1 << kw as u32);
+
+        Self { quote_style: dialect.quote_style(), reserved }
+    }
+
+    fn is_reserved(&self, word: &str) -> bool {
+        Keyword::try_from(word).is_ok_and(|kw| self.reserved & 1 << kw as u32 != 0)
+    }
+}
+
+// Sets the style nodes are displayed in, restoring the previous style when dropped
+pub(crate) struct DisplayStyleGuard(DisplayStyle);
+
+impl DisplayStyleGuard {
+    pub(crate) fn set(dialect: &dyn Dialect) -> Self {
+        Self(DISPLAY_STYLE.replace(DisplayStyle::new(dialect)))
+    }
+}
+
+impl std::ops::Drop for DisplayStyleGuard {
+    fn drop(&mut self) {
+        DISPLAY_STYLE.set(self.0);
+    }
+}
+
+impl Display for Name {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt_name(f, self, false)
+    }
+}
+
+// Writes a name bare if it was bare in the source and would tokenise back to the same identifier,
+// otherwise quoted. Reserved keywords can be left bare for function names, where quoting a
+// built-in function's name can make it refer to a user-defined function instead
+fn fmt_name(f: &mut Formatter<'_>, name: &Name, allow_reserved: bool) -> fmt::Result {
+    let style = DISPLAY_STYLE.get();
+    let mut chars = name.value.chars();
+    let bare = !name.quoted
+        && chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphabetic() || c.is_ascii_digit() || c == '_')
+        && (allow_reserved || !style.is_reserved(&name.value));
+
+    if bare {
+        return f.write_str(&name.value);
+    }
+
+    match style.quote_style {
+        QuoteStyle::MySql => write!(f, "`{}`", name.value.replace('`', "``")),
+        QuoteStyle::Ansi | QuoteStyle::Postgres => {
+            write!(f, "\"{}\"", name.value.replace('"', "\"\""))
+        }
+    }
+}
+
+// A function's name, which is left bare if it's a keyword
+struct FunctionName<'a>(&'a Ident);
+
+impl Display for FunctionName<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let parts = match self.0 {
+            Ident::Single(name) => std::slice::from_ref(name),
+            Ident::Compound(parts) => parts,
+        };
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            fmt_name(f, part, true)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Writes `expr`, parenthesised if it binds looser than `min`
+fn fmt_operand(f: &mut Formatter<'_>, expr: &Expr, min: u8) -> fmt::Result {
+    if expr.precedence() < min {
+        write!(f, "({expr})")
+    } else {
+        write!(f, "{expr}")
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            StatementKind::Select(select) => write!(f, "{select}"),
+            StatementKind::Insert(insert) => write!(f, "{insert}"),
+            StatementKind::Update(update) => write!(f, "{update}"),
+            StatementKind::Delete(delete) => write!(f, "{delete}"),
+            StatementKind::Create(create) => write!(f, "{create}"),
+            StatementKind::CreateIndex(create) => write!(f, "{create}"),
+            StatementKind::CreateView(create) => write!(f, "{create}"),
+            StatementKind::AlterTable(alter) => write!(f, "{alter}"),
+            StatementKind::Drop(drop) => write!(f, "{drop}"),
+            StatementKind::Truncate(truncate) => write!(f, "{truncate}"),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(n) => f.write_str(n),
+            // Debug keeps the decimal point or exponent, so the float doesn't read back as an int
+            Value::Int(n) => write!(f, "{n}"),
+            Value::Float(n) => write!(f, "{n:?}"),
+            Value::Decimal(n) => write!(f, "{n}"),
+            Value::String(s) if DISPLAY_STYLE.get().quote_style == QuoteStyle::MySql => {
+                write!(f, "'{}'", s.replace('\\', "\\\\").replace('\'', "''"))
+            }
+            Value::String(s) => write!(f, "'{}'", s.replace('\'', "''")),
+            Value::HexString(s) => write!(f, "X'{s}'"),
+            Value::Bool(true) => f.write_str("TRUE"),
+            Value::Bool(false) => f.write_str("FALSE"),
+            Value::Null => f.write_str("NULL"),
+        }
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Op::Eq => "=",
+            Op::Neq => "<>",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::And => "AND",
+            Op::Or => "OR",
+            Op::Plus => "+",
+            Op::Minus => "-",
+            Op::Multiply => "*",
+            Op::Divide => "/",
+            Op::Modulo => "%",
+            Op::Concat => "||",
+        })
+    }
+}
+
+impl Display for UnaryOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UnaryOp::Plus => "+",
+            UnaryOp::Minus => "-",
+            UnaryOp::Not => "NOT ",
+        })
+    }
+}
+
+impl Display for Ident {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Ident::Single(name) => write!(f, "{name}"),
+            Ident::Compound(parts) => write!(f, "{}", Separated(parts, ".")),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let prec = self.precedence();
+        match &self.kind {
+            ExprKind::Ident(ident) => write!(f, "{ident}"),
+            ExprKind::Wildcard => f.write_str("*"),
+            ExprKind::Default => f.write_str("DEFAULT"),
+            ExprKind::QualifiedWildcard(parts) => write!(f, "{}.*", Separated(parts, ".")),
+            ExprKind::Value(value) => write!(f, "{value}"),
+            ExprKind::IsNull(expr) => {
+                fmt_operand(f, expr, prec)?;
+                f.write_str(" IS NULL")
+            }
+            ExprKind::IsNotNull(expr) => {
+                fmt_operand(f, expr, prec)?;
+                f.write_str(" IS NOT NULL")
+            }
+            ExprKind::InList { expr, list, negated } => {
+                fmt_operand(f, expr, prec)?;
+                let not = if *negated { " NOT" } else { "" };
+                write!(f, "{not} IN ({})", comma_separated(list))
+            }
+            ExprKind::Between { expr, negated, low, high } => {
+                fmt_operand(f, expr, prec)?;
+                let not = if *negated { " NOT" } else { "" };
+                write!(f, "{not} BETWEEN ")?;
+                fmt_operand(f, low, prec + 1)?;
+                f.write_str(" AND ")?;
+                fmt_operand(f, high, prec + 1)
+            }
+            // Binary operators are left associative, so only the right operand needs parens at
+            // the same precedence
+            ExprKind::BinaryOp { left, op, right } => {
+                fmt_operand(f, left, prec)?;
+                write!(f, " {op} ")?;
+                fmt_operand(f, right, prec + 1)
+            }
+            ExprKind::SubQuery(query) => write!(f, "({query})"),
+            ExprKind::GroupingSets(sets) => write!(f, "GROUPING SETS ({})", GroupingSets(sets)),
+            ExprKind::Rollup(sets) => write!(f, "ROLLUP ({})", GroupingSets(sets)),
+            ExprKind::Cube(sets) => write!(f, "CUBE ({})", GroupingSets(sets)),
+            ExprKind::Function { name, args, distinct, filter } => {
+                let distinct = if *distinct { "DISTINCT " } else { "" };
+                write!(f, "{}({distinct}{})", FunctionName(name), comma_separated(args))?;
+                if let Some(filter) = filter {
+                    write!(f, " FILTER (WHERE {filter})")?;
+                }
+                Ok(())
+            }
+            ExprKind::UnaryOp { op, expr } => {
+                write!(f, "{op}")?;
+                fmt_operand(f, expr, prec + 1)
+            }
+        }
+    }
+}
+
+struct GroupingSets<'a>(&'a [Vec<Expr>]);
+
+impl Display for GroupingSets<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, set) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            match set.as_slice() {
+                [expr] => write!(f, "{expr}")?,
+                set => write!(f, "({})", comma_separated(set))?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "SELECT {} FROM {}", comma_separated(&self.projection), self.from)?;
+        for join in &self.joins {
+            let sep = if join.ty == JoinType::Comma { ", " } else { " " };
+            write!(f, "{sep}{join}")?;
+        }
+        if let Some(filter) = &self.filter {
+            write!(f, " WHERE {filter}")?;
+        }
+        if !self.group.is_empty() {
+            write!(f, " GROUP BY {}", comma_separated(&self.group))?;
+        }
+        if let Some(having) = &self.having {
+            write!(f, " HAVING {having}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for FromTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let alias = match &self.kind {
+            FromTableKind::Table { name, alias } => {
+                write!(f, "{name}")?;
+                alias.as_ref().map(|alias| (" AS ", alias))
+            }
+            FromTableKind::Derived { lateral, query, alias } => {
+                let lateral = if *lateral { "LATERAL " } else { "" };
+                write!(f, "{lateral}({query})")?;
+                alias.as_ref().map(|alias| (" ", alias))
+            }
+        };
+
+        match alias {
+            Some((sep, alias)) => write!(f, "{sep}{}", alias),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for Join {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.constraint == JoinConstraint::Natural {
+            f.write_str("NATURAL ")?;
+        }
+
+        let ty = match self.ty {
+            JoinType::Inner => "JOIN ",
+            JoinType::Left => "LEFT JOIN ",
+            JoinType::Right => "RIGHT JOIN ",
+            JoinType::Full => "FULL JOIN ",
+            JoinType::Cross => "CROSS JOIN ",
+            JoinType::Comma => "",
+        };
+        write!(f, "{ty}{}", self.from)?;
+
+        match &self.constraint {
+            JoinConstraint::On(expr) => write!(f, " ON {expr}"),
+            JoinConstraint::Using(columns) => {
+                write!(f, " USING ({})", comma_separated(columns))
+            }
+            JoinConstraint::Natural | JoinConstraint::None => Ok(()),
+        }
+    }
+}
+
+impl Display for OrderByExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)?;
+        if self.desc {
+            f.write_str(" DESC")?;
+        }
+        match self.nulls_first {
+            Some(true) => f.write_str(" NULLS FIRST"),
+            Some(false) => f.write_str(" NULLS LAST"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for SelectItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            SelectItemKind::Expr(expr) => write!(f, "{expr}"),
+            SelectItemKind::AliasedExpr { expr, alias } => write!(f, "{expr} AS {}", alias),
+            SelectItemKind::QualifiedWildcard(parts) => {
+                write!(f, "{}.*", Separated(parts, "."))
+            }
+            SelectItemKind::Wildcard => f.write_str("*"),
+        }
+    }
+}
+
+impl Display for Select {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.body)?;
+        if !self.order.is_empty() {
+            write!(f, " ORDER BY {}", comma_separated(&self.order))?;
+        }
+        if let Some(limit) = &self.limit {
+            write!(f, " LIMIT {limit}")?;
+        }
+        if let Some(offset) = &self.offset {
+            write!(f, " OFFSET {offset}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Insert {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "INSERT INTO {}", self.table)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", comma_separated(&self.columns))?;
+        }
+        write!(f, " {}", self.source)?;
+        if let Some(on_conflict) = &self.on_conflict {
+            write!(f, " {on_conflict}")?;
+        }
+
+        write!(f, "{}", Returning(&self.returning))
+    }
+}
+
+impl Display for OnConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            OnConflict::OnConflict { columns, action } => {
+                f.write_str("ON CONFLICT ")?;
+                if !columns.is_empty() {
+                    write!(f, "({}) ", comma_separated(columns))?;
+                }
+                write!(f, "{action}")
+            }
+            OnConflict::DuplicateKeyUpdate(set) => {
+                write!(f, "ON DUPLICATE KEY UPDATE {}", comma_separated(set))
+            }
+        }
+    }
+}
+
+impl Display for ConflictAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictAction::DoNothing => f.write_str("DO NOTHING"),
+            ConflictAction::DoUpdate { set, filter } => {
+                write!(f, "DO UPDATE SET {}", comma_separated(set))?;
+                if let Some(filter) = filter {
+                    write!(f, " WHERE {filter}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+// Writes ` RETURNING items` unless there are no items
+struct Returning<'a>(&'a [SelectItem]);
+
+impl Display for Returning<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.0.is_empty() {
+            write!(f, " RETURNING {}", comma_separated(self.0))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for InsertSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertSource::Values(rows) => {
+                f.write_str("VALUES ")?;
+                for (i, row) in rows.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "({})", comma_separated(row))?;
+                }
+                Ok(())
+            }
+            InsertSource::Select(select) => write!(f, "{select}"),
+            InsertSource::DefaultValues => f.write_str("DEFAULT VALUES"),
+        }
+    }
+}
+
+impl Display for Update {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "UPDATE {} SET {}", self.table, comma_separated(&self.set))?;
+        if let Some(filter) = &self.filter {
+            write!(f, " WHERE {filter}")?;
+        }
+
+        write!(f, "{}", Returning(&self.returning))
+    }
+}
+
+impl Display for Set {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.column, self.expr)
+    }
+}
+
+impl Display for Delete {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "DELETE FROM {}", self.table)?;
+        if let Some(filter) = &self.filter {
+            write!(f, " WHERE {filter}")?;
+        }
+
+        write!(f, "{}", Returning(&self.returning))
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let scale = self.scale as usize;
+        let digits = format!("{:0>width$}", self.mantissa.unsigned_abs(), width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let frac = if frac.is_empty() { "0" } else { frac };
+
+        write!(f, "{sign}{int}.{frac}")
+    }
+}
+
+impl Display for Create {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("CREATE TABLE ")?;
+        if self.if_not_exists {
+            f.write_str("IF NOT EXISTS ")?;
+        }
+        write!(f, "{} (", self.name)?;
+        let columns = self.columns.iter().map(|c| c as &dyn Display);
+        let constraints = self.constraints.iter().map(|c| c as &dyn Display);
+        for (i, item) in columns.chain(constraints).enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{item}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl Display for CreateIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("CREATE ")?;
+        if self.unique {
+            f.write_str("UNIQUE ")?;
+        }
+        f.write_str("INDEX ")?;
+        if self.if_not_exists {
+            f.write_str("IF NOT EXISTS ")?;
+        }
+        write!(f, "{} ON {} ({})", self.name, self.table, comma_separated(&self.columns))?;
+        if let Some(filter) = &self.filter {
+            write!(f, " WHERE {filter}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for CreateView {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("CREATE ")?;
+        if self.or_replace {
+            f.write_str("OR REPLACE ")?;
+        }
+        write!(f, "VIEW {}", self.name)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", comma_separated(&self.columns))?;
+        }
+        write!(f, " AS {}", self.query)
+    }
+}
+
+impl Display for ColumnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnType::SmallInt => f.write_str("SMALLINT"),
+            ColumnType::Int => f.write_str("INT"),
+            ColumnType::BigInt => f.write_str("BIGINT"),
+            ColumnType::Boolean => f.write_str("BOOLEAN"),
+            ColumnType::Real => f.write_str("REAL"),
+            ColumnType::Double => f.write_str("DOUBLE PRECISION"),
+            ColumnType::Decimal { precision: None, .. } => f.write_str("DECIMAL"),
+            ColumnType::Decimal { precision: Some(p), scale: None } => write!(f, "DECIMAL({p})"),
+            ColumnType::Decimal { precision: Some(p), scale: Some(s) } => {
+                write!(f, "DECIMAL({p}, {s})")
+            }
+            ColumnType::Char(None) => f.write_str("CHAR"),
+            ColumnType::Char(Some(len)) => write!(f, "CHAR({len})"),
+            ColumnType::Varchar(max) => write!(f, "VARCHAR({max})"),
+            ColumnType::Text => f.write_str("TEXT"),
+            ColumnType::Date => f.write_str("DATE"),
+            ColumnType::Time { with_time_zone } => {
+                f.write_str("TIME")?;
+                fmt_time_zone(f, *with_time_zone)
+            }
+            ColumnType::Timestamp { with_time_zone } => {
+                f.write_str("TIMESTAMP")?;
+                fmt_time_zone(f, *with_time_zone)
+            }
+            ColumnType::Blob => f.write_str("BLOB"),
+            ColumnType::Bytea => f.write_str("BYTEA"),
+            ColumnType::Json => f.write_str("JSON"),
+            ColumnType::Array(ty) => write!(f, "{ty}[]"),
+            ColumnType::Custom(name, args) if args.is_empty() => write!(f, "{}", name),
+            ColumnType::Custom(name, args) => write!(f, "{}({})", name, args.join(", ")),
+        }
+    }
+}
+
+fn fmt_time_zone(f: &mut Formatter<'_>, with_time_zone: bool) -> fmt::Result {
+    if with_time_zone {
+        f.write_str(" WITH TIME ZONE")?;
+    }
+
+    Ok(())
+}
+
+impl Display for ColumnDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.ty)?;
+        for constraint in &self.constraints {
+            write!(f, " {constraint}")?;
+        }
+
+        Ok(())
+    }
+}
+
+// Writes `CONSTRAINT name ` if the constraint is named
+struct ConstraintName<'a>(&'a Option<Name>);
+
+impl Display for ConstraintName<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(name) => write!(f, "CONSTRAINT {} ", name),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for ColumnConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", ConstraintName(&self.name))?;
+        match &self.kind {
+            ColumnConstraintKind::Null => f.write_str("NULL"),
+            ColumnConstraintKind::NotNull => f.write_str("NOT NULL"),
+            ColumnConstraintKind::Default(expr) => write!(f, "DEFAULT {expr}"),
+            ColumnConstraintKind::PrimaryKey => f.write_str("PRIMARY KEY"),
+            ColumnConstraintKind::Unique => f.write_str("UNIQUE"),
+            ColumnConstraintKind::Check(expr) => write!(f, "CHECK ({expr})"),
+            ColumnConstraintKind::References(references) => write!(f, "{references}"),
+        }
+    }
+}
+
+impl Display for TableConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", ConstraintName(&self.name))?;
+        match &self.kind {
+            TableConstraintKind::PrimaryKey(columns) => {
+                write!(f, "PRIMARY KEY ({})", comma_separated(columns))
+            }
+            TableConstraintKind::Unique(columns) => {
+                write!(f, "UNIQUE ({})", comma_separated(columns))
+            }
+            TableConstraintKind::ForeignKey { columns, references } => {
+                write!(f, "FOREIGN KEY ({}) {references}", comma_separated(columns))
+            }
+            TableConstraintKind::Check(expr) => write!(f, "CHECK ({expr})"),
+        }
+    }
+}
+
+impl Display for References {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "REFERENCES {}", self.table)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", comma_separated(&self.columns))?;
+        }
+        if let Some(action) = self.on_delete {
+            write!(f, " ON DELETE {action}")?;
+        }
+        if let Some(action) = self.on_update {
+            write!(f, " ON UPDATE {action}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for ReferentialAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReferentialAction::NoAction => "NO ACTION",
+            ReferentialAction::Restrict => "RESTRICT",
+            ReferentialAction::Cascade => "CASCADE",
+            ReferentialAction::SetNull => "SET NULL",
+            ReferentialAction::SetDefault => "SET DEFAULT",
+        })
+    }
+}
+
+// Writes ` CASCADE` if set, RESTRICT is the default so isn't written
+struct Cascade(bool);
+
+impl Display for Cascade {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0 {
+            f.write_str(" CASCADE")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn if_exists(if_exists: bool) -> &'static str {
+    if if_exists {
+        "IF EXISTS "
+    } else {
+        ""
+    }
+}
+
+impl Display for AlterTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ALTER TABLE {}{} ", if_exists(self.if_exists), self.name)?;
+        write!(f, "{}", comma_separated(&self.operations))
+    }
+}
+
+impl Display for AlterTableOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AlterTableOperation::AddColumn { if_not_exists, column } => {
+                let if_not_exists = if *if_not_exists { "IF NOT EXISTS " } else { "" };
+                write!(f, "ADD COLUMN {if_not_exists}{column}")
+            }
+            AlterTableOperation::DropColumn { name, if_exists: exists, cascade } => {
+                write!(f, "DROP COLUMN {}{}{}", if_exists(*exists), name, Cascade(*cascade))
+            }
+            AlterTableOperation::RenameColumn { from, to } => {
+                write!(f, "RENAME COLUMN {} TO {}", from, to)
+            }
+            AlterTableOperation::AlterColumn { name, op } => {
+                write!(f, "ALTER COLUMN {} {op}", name)
+            }
+            AlterTableOperation::AddConstraint(constraint) => write!(f, "ADD {constraint}"),
+            AlterTableOperation::DropConstraint { name, if_exists: exists, cascade } => {
+                let (exists, name, cascade) = (if_exists(*exists), name, Cascade(*cascade));
+                write!(f, "DROP CONSTRAINT {exists}{name}{cascade}")
+            }
+            AlterTableOperation::RenameTo(name) => write!(f, "RENAME TO {}", name),
+        }
+    }
+}
+
+impl Display for AlterColumnOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AlterColumnOperation::SetNotNull => f.write_str("SET NOT NULL"),
+            AlterColumnOperation::DropNotNull => f.write_str("DROP NOT NULL"),
+            AlterColumnOperation::SetDefault(expr) => write!(f, "SET DEFAULT {expr}"),
+            AlterColumnOperation::DropDefault => f.write_str("DROP DEFAULT"),
+            AlterColumnOperation::SetType(ty) => write!(f, "SET DATA TYPE {ty}"),
+        }
+    }
+}
+
+impl Display for Drop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let object = match self.object {
+            ObjectType::Table => "TABLE",
+            ObjectType::Index => "INDEX",
+            ObjectType::View => "VIEW",
+        };
+        let names = comma_separated(&self.names);
+        write!(f, "DROP {object} {}{names}{}", if_exists(self.if_exists), Cascade(self.cascade))
+    }
+}
+
+impl Display for Truncate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "TRUNCATE TABLE {}{}", comma_separated(&self.tables), Cascade(self.cascade))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dialect::{Dialect, GenericDialect, MySqlDialect, PostgresDialect, SqliteDialect},
+        parser::Parser,
+    };
+
+    use super::Statement;
+
+    fn parse(input: &str) -> Vec<Statement> {
+        Parser::new(input, &GenericDialect).unwrap().parse().unwrap()
+    }
+
+    fn print(statements: &[Statement]) -> String {
+        statements.iter().map(|s| format!("{s};")).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn test_display() {
+        let cases = [
+            ("select * from t1 limit 20, 10", "SELECT * FROM t1 LIMIT 10 OFFSET 20"),
+            (
+                "select (a + b) * c, a + (b * c), (a - b) - c, a - (b - c) from t1",
+                "SELECT (a + b) * c, a + b * c, a - b - c, a - (b - c) FROM t1",
+            ),
+            (
+                "select not (a = 1) or b and not c from t1",
+                "SELECT NOT a = 1 OR b AND NOT c FROM t1",
+            ),
+            (
+                "select (not a) = b, -(-a), -(a * b), (a is null) is null from t1",
+                "SELECT (NOT a) = b, -(-a), -(a * b), a IS NULL IS NULL FROM t1",
+            ),
+            (
+                "select a between (b and c) and d from t1",
+                "SELECT a BETWEEN (b AND c) AND d FROM t1",
+            ),
+            (
+                "select \"select\", \"a b\", 'it''s', first from \"t\"\"1\"",
+                "SELECT \"select\", \"a b\", 'it''s', first FROM \"t\"\"1\"",
+            ),
+            (
+                "select count(distinct t1.*) filter (where a) from t1 \
+                 natural left join t2, t3 cross join t4",
+                "SELECT count(DISTINCT t1.*) FILTER (WHERE a) FROM t1 \
+                 NATURAL LEFT JOIN t2, t3 CROSS JOIN t4",
+            ),
+            (
+                "insert into t1 values (1, 'a'), (2, null)",
+                "INSERT INTO t1 VALUES (1, 'a'), (2, NULL)",
+            ),
+            (
+                "insert into s1.t1 (\"c 1\", c2) select * from t2",
+                "INSERT INTO s1.t1 (\"c 1\", c2) SELECT * FROM t2",
+            ),
+            (
+                "create table t1 (c1 int, c2 varchar(10))",
+                "CREATE TABLE t1 (c1 INT, c2 VARCHAR(10))",
+            ),
+            (
+                "create table if not exists t1 (c1 int constraint pk primary key, \
+                 c2 int not null default -1 references t2 on delete set default, \
+                 constraint u1 unique (c1, c2), foreign key (c2) references t2 (c1))",
+                "CREATE TABLE IF NOT EXISTS t1 (c1 INT CONSTRAINT pk PRIMARY KEY, \
+                 c2 INT NOT NULL DEFAULT -1 REFERENCES t2 ON DELETE SET DEFAULT, \
+                 CONSTRAINT u1 UNIQUE (c1, c2), FOREIGN KEY (c2) REFERENCES t2 (c1))",
+            ),
+            ("select 1e3, 0x1F, x'ab' from t1", "SELECT 1e3, 0x1F, X'ab' FROM t1"),
+            (
+                "alter table t1 add c1 int, drop c2 restrict, rename c3 to c4, alter c5 type text",
+                "ALTER TABLE t1 ADD COLUMN c1 INT, DROP COLUMN c2, RENAME COLUMN c3 TO c4, \
+                 ALTER COLUMN c5 SET DATA TYPE TEXT",
+            ),
+            ("drop view v1, v2 restrict", "DROP VIEW v1, v2"),
+            ("truncate t1 cascade", "TRUNCATE TABLE t1 CASCADE"),
+            (
+                "create table t1 (c1 integer, c2 double, c3 numeric(5, 2), c4 timestamp without \
+                 time zone, c5 time with time zone, c6 bool array, c7 \"My Type\"(1, x))",
+                "CREATE TABLE t1 (c1 INT, c2 DOUBLE PRECISION, c3 DECIMAL(5, 2), c4 TIMESTAMP, \
+                 c5 TIME WITH TIME ZONE, c6 BOOLEAN[], c7 \"My Type\"(1, x))",
+            ),
+        ];
+
+        for (input, want) in cases {
+            assert_eq!(want, print(&parse(input)).trim_end_matches(';'), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let statements = [
+            "CREATE TABLE t1 (c1 INT, c2 VARCHAR(1024))",
+            "create table t1 (c1 bigint[], c2 char(1), c3 decimal(1), c4 text, c5 date, c6 json, \
+             c7 geometry(point, 4326), c8 enum('a', 'b'), c9 blob, c10 bytea, c11 real)",
+            "create table if not exists t1 (c1 int primary key check (c1 > 0), c2 int unique null, \
+             c3 int references t2 (c1) on update restrict on delete no action, \
+             constraint pk primary key (c1, c2), check (c1 < c2))",
+            "create table t1 (primary key (c1), unique (c2))",
+            "SELECT * FROM t1 ORDER BY c1, c2 DESC NULLS LAST, c3 ASC NULLS FIRST \
+             LIMIT 10 OFFSET 20",
+            "SELECT * FROM t1 LIMIT 20, 10",
+            "select * from t1 group by c1, c2 having c1 > 5",
+            "select * from t1 \
+             group by c1, grouping sets ((c1, c2), c3, ()), rollup (c1, (c2, c3)), cube (c1)",
+            "select first from t1 order by last nulls first",
+            "select \"c1\" || 'c2' from t1",
+            "select t1.*, *, s1.t1.c1 from t1",
+            "select *, c1 * 2, t1.* from t1",
+            "select * from table1 as t1",
+            "select * from t1 join t2 on t1.c1 = t2.c1 join t3 using (c2, c3)",
+            "select * from t1 join (select * from t1) t1 using (c1)",
+            "select * from t1 left join t2 on true right outer join t3 using (c1) \
+             full join t4 using (c1) inner join t5 on false cross join t6 \
+             natural left join t7, t8 natural join lateral (select * from t9) t9",
+            "insert into t1 values (1, 2), ('1', '2')",
+            "insert into s1.t1 (c1, c2) values (default, 1), (2, default)",
+            "insert into t1 select c1, c2 from t2 where c1 > 0 order by c2 limit 1",
+            "insert into t1 default values",
+            "insert into t1 values (1) on conflict do nothing",
+            "insert into t1 select * from t2 on conflict (c1, \"c 2\") do update set c1 = 1, \
+             c2 = excluded.c2 where t1.c3 <> 0 returning *",
+            "insert into t1 (c1) values (1) on duplicate key update c1 = c1 + 1",
+            "insert into t1 default values returning c1, c2 as a",
+            "update t1 set c1 = 1 returning t1.*",
+            "delete from t1 where c1 = 1 returning c1, c2",
+            "select * from db.s1.t1 join s1.t2 using (c1)",
+            "update t1 set c1 = 1, c2 = '2' where 1 = 1",
+            "delete from t1 where 1 = 1",
+            "alter table if exists s1.t1 add column c1 int default 1 not null, \
+             add constraint pk primary key (c1), drop column if exists c2 cascade, \
+             drop constraint if exists u1, rename column c3 to c4, alter column c1 set default -1, \
+             alter c1 drop default, alter c1 set not null, alter c1 drop not null, \
+             alter c1 type bigint, rename to t2",
+            "drop table if exists t1, s1.t2 cascade",
+            "drop index i1",
+            "drop view if exists v1 restrict",
+            "truncate table t1, t2 cascade",
+            "truncate t1",
+            "create unique index if not exists i1 on s1.t1 (c1 desc nulls last, lower(c2)) \
+             where c3 is not null",
+            "create index i1 on t1 (c1 asc)",
+            "create or replace view s1.v1 (a, b) as \
+             select c1, count(*) from t1 group by c1 order by c1 limit 10",
+            "create view v1 as select * from t1 join t2 using (c1)",
+        ];
+
+        let exprs = [
+            "c1 < 5",
+            "c1 < 5 and c2 in (1, '2', 3, '4')",
+            "c1 < 5 and c2 not in (1, '2', 3, '4')",
+            "(c1 < 5) and (c2 not in (1, '2', 3, '4'))",
+            "c1 < (5 < c2) AND (c1 < 5) < c2",
+            "c1 between 0 and 200",
+            "c1 not between 0 and 200",
+            "s1.t1.c1 > 5",
+            "1 < (select * from t1 join t2 using (c1) where t1.c2 > t2.c2)",
+            "c1 is not null and c2 is null",
+            "count(*) > lower(c1)",
+            "count(distinct t1.c1) filter (where c2 is not null)",
+            "s1.coalesce(t1.*, max(c1), 1)",
+            "price * qty - discount > 0 AND NOT archived",
+            "-a + b % 2 || c / -(d + 1)",
+            "not a = 1 or b",
+        ];
+
+        let inputs = statements
+            .iter()
+            .map(|s| s.to_string())
+            .chain(exprs.iter().map(|e| format!("select {e} from t1")));
+
+        let dialects: [&dyn Dialect; 4] =
+            [&GenericDialect, &PostgresDialect, &MySqlDialect, &SqliteDialect];
+        for input in inputs {
+            for dialect in dialects {
+                // Not every input is valid in every dialect
+                let Ok(want) = Parser::new(&input, dialect).and_then(|mut p| p.parse()) else {
+                    continue;
+                };
+                let printed: Vec<_> = want.iter().map(|s| s.display(dialect).to_string()).collect();
+                let printed = printed.join(";\n");
+                let have = Parser::new(&printed, dialect).and_then(|mut p| p.parse());
+                assert_eq!(Ok(want), have, "{input} printed for {dialect:?} as {printed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_display_for_dialect() {
+        let input = "select `a b`, `c``d`, 'e\\\\f', `select` from t";
+        let have = Parser::new(input, &MySqlDialect).unwrap().parse().unwrap();
+
+        let want = "SELECT `a b`, `c``d`, 'e\\\\f', `select` FROM t";
+        assert_eq!(want, have[0].display(&MySqlDialect).to_string());
+
+        let want = "SELECT \"a b\", \"c`d\", 'e\\f', \"select\" FROM t";
+        assert_eq!(want, have[0].to_string());
+    }
+
+    #[test]
+    fn test_display_keeps_quoting() {
+        let cases: [(&dyn Dialect, &str, &str); 4] = [
+            (
+                &PostgresDialect,
+                "select \"UserId\", UserId, \"user_id\" from \"Users\" as u",
+                "SELECT \"UserId\", UserId, \"user_id\" FROM \"Users\" AS u",
+            ),
+            (
+                &GenericDialect,
+                "create table \"T1\" (\"C1\" int, constraint \"Pk\" primary key (\"C1\"))",
+                "CREATE TABLE \"T1\" (\"C1\" INT, CONSTRAINT \"Pk\" PRIMARY KEY (\"C1\"))",
+            ),
+            (
+                &MySqlDialect,
+                "select replace(a, 'x', 'y'), date(b), `Sum`(d) from t1",
+                "SELECT replace(a, 'x', 'y'), date(b), `Sum`(d) FROM t1",
+            ),
+            (
+                &PostgresDialect,
+                "select replace(a, 'x', 'y'), \"Lower\"(b) from t1",
+                "SELECT replace(a, 'x', 'y'), \"Lower\"(b) FROM t1",
+            ),
+        ];
+
+        for (dialect, input, want) in cases {
+            let parsed = Parser::new(input, dialect).unwrap().parse().unwrap();
+            let have = parsed[0].display(dialect).to_string();
+            assert_eq!(want, have, "{dialect:?}");
+
+            let reparsed = Parser::new(&have, dialect).unwrap().parse().unwrap();
+            assert_eq!(parsed, reparsed);
+            assert_eq!(want, reparsed[0].display(dialect).to_string(), "{dialect:?}");
+        }
+    }
+
+    #[test]
+    fn test_display_unreserved_keywords() {
+        let input = "select Date, Type, First from t1 where Type = 1 order by First";
+        let want = "SELECT Date, Type, First FROM t1 WHERE Type = 1 ORDER BY First";
+
+        let dialects: [&dyn Dialect; 4] =
+            [&GenericDialect, &PostgresDialect, &MySqlDialect, &SqliteDialect];
+        for dialect in dialects {
+            let parsed = Parser::new(input, dialect).unwrap().parse().unwrap();
+            assert_eq!(want, parsed[0].display(dialect).to_string(), "{dialect:?}");
+        }
+    }
+}
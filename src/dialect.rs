@@ -0,0 +1,170 @@
+use crate::{ast::Op, tokeniser::Keyword, QuoteStyle};
+
+/// A flavour of SQL, controlling how source is tokenised and which constructs the parser accepts
+pub trait Dialect: std::fmt::Debug {
+    /// The quote characters used for string literals and identifiers
+    fn quote_style(&self) -> QuoteStyle;
+
+    /// Whether `#` starts a line comment
+    fn supports_hash_comments(&self) -> bool {
+        false
+    }
+
+    /// Whether `/* */` block comments can be nested
+    fn supports_nested_comments(&self) -> bool {
+        false
+    }
+
+    /// Whether numbers can contain `_` between digits, e.g. `1_000_000`
+    fn supports_numeric_separators(&self) -> bool {
+        false
+    }
+
+    /// Whether `kw` is a keyword, words that aren't keywords are tokenised as identifiers
+    fn is_keyword(&self, kw: Keyword) -> bool {
+        let _ = kw;
+        true
+    }
+
+    /// Whether `kw` is reserved, non-reserved keywords can also be used as unquoted identifiers
+    fn is_reserved(&self, kw: Keyword) -> bool {
+        !matches!(
+            kw,
+            Keyword::Action
+                | Keyword::Bigint
+                | Keyword::Blob
+                | Keyword::Bool
+                | Keyword::Boolean
+                | Keyword::Bytea
+                | Keyword::Cascade
+                | Keyword::Conflict
+                | Keyword::Char
+                | Keyword::Cube
+                | Keyword::Data
+                | Keyword::Date
+                | Keyword::Decimal
+                | Keyword::Double
+                | Keyword::Duplicate
+                | Keyword::Filter
+                | Keyword::First
+                | Keyword::Grouping
+                | Keyword::If
+                | Keyword::Index
+                | Keyword::Integer
+                | Keyword::Json
+                | Keyword::Key
+                | Keyword::Last
+                | Keyword::No
+                | Keyword::Nothing
+                | Keyword::Nulls
+                | Keyword::Numeric
+                | Keyword::Offset
+                | Keyword::Precision
+                | Keyword::Real
+                | Keyword::Rename
+                | Keyword::Replace
+                | Keyword::Restrict
+                | Keyword::Rollup
+                | Keyword::Sets
+                | Keyword::Smallint
+                | Keyword::Text
+                | Keyword::Time
+                | Keyword::Timestamp
+                | Keyword::Type
+                | Keyword::View
+                | Keyword::Without
+                | Keyword::Zone
+        )
+    }
+
+    /// Whether the binary operator `op` is supported
+    fn supports_operator(&self, op: &Op) -> bool {
+        let _ = op;
+        true
+    }
+
+    /// Whether statements starting with `kw` are allowed
+    fn supports_statement(&self, kw: Keyword) -> bool {
+        let _ = kw;
+        true
+    }
+}
+
+/// Whether `kw` has to be quoted to be used as an identifier in `dialect`. Any other keyword is
+/// parsed as an unquoted identifier written as it is in the source, and displayed bare
+pub(crate) fn is_reserved(dialect: &dyn Dialect, kw: Keyword) -> bool {
+    dialect.is_keyword(kw) && dialect.is_reserved(kw)
+}
+
+/// A permissive dialect using ANSI quoting
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GenericDialect;
+
+impl Dialect for GenericDialect {
+    fn quote_style(&self) -> QuoteStyle {
+        QuoteStyle::Ansi
+    }
+
+    fn supports_hash_comments(&self) -> bool {
+        true
+    }
+
+    fn supports_numeric_separators(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PostgresDialect;
+
+impl Dialect for PostgresDialect {
+    fn quote_style(&self) -> QuoteStyle {
+        QuoteStyle::Postgres
+    }
+
+    fn supports_nested_comments(&self) -> bool {
+        true
+    }
+
+    fn supports_numeric_separators(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MySqlDialect;
+
+impl Dialect for MySqlDialect {
+    fn quote_style(&self) -> QuoteStyle {
+        QuoteStyle::MySql
+    }
+
+    fn supports_hash_comments(&self) -> bool {
+        true
+    }
+
+    fn is_keyword(&self, kw: Keyword) -> bool {
+        !matches!(kw, Keyword::Filter | Keyword::Grouping | Keyword::Nulls | Keyword::Sets)
+    }
+
+    fn supports_operator(&self, op: &Op) -> bool {
+        // `||` is a synonym for OR unless PIPES_AS_CONCAT is enabled
+        *op != Op::Concat
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SqliteDialect;
+
+impl Dialect for SqliteDialect {
+    fn quote_style(&self) -> QuoteStyle {
+        QuoteStyle::Ansi
+    }
+
+    fn is_keyword(&self, kw: Keyword) -> bool {
+        !matches!(
+            kw,
+            Keyword::Cube | Keyword::Grouping | Keyword::Lateral | Keyword::Rollup | Keyword::Sets
+        )
+    }
+}
@@ -0,0 +1,664 @@
+use crate::{
+    ast::{
+        AlterTable, ConflictAction, Create, CreateView, Delete, DisplayStyleGuard, Expr, ExprKind,
+        Insert, InsertSource, Join, JoinConstraint, JoinType, OnConflict, Op, Query, Select,
+        SelectItem, SelectItemKind, Statement, StatementKind, Update,
+    },
+    dialect::Dialect,
+    parser::{Parser, ParserError},
+    tokeniser::{Span, Token, TokenWithSpan, Tokeniser},
+};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum KeywordCase {
+    Upper,
+    Lower,
+}
+
+/// Controls the layout produced by [`format`]
+#[derive(Debug, Clone, Copy)]
+pub struct FormatOptions {
+    pub keyword_case: KeywordCase,
+    /// Lines longer than this are broken up where possible
+    pub max_width: usize,
+    /// The number of spaces each nested block is indented by
+    pub indent: usize,
+    /// Put every SELECT item and SET assignment on its own line
+    pub one_item_per_line: bool,
+    /// Line up the `AS` of aliased SELECT items when they're on their own lines
+    pub align_aliases: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            keyword_case: KeywordCase::Upper,
+            max_width: 80,
+            indent: 4,
+            one_item_per_line: true,
+            align_aliases: true,
+        }
+    }
+}
+
+/// Parses `src` and prints it back in a consistent layout. `dialect` is the dialect `src` is
+/// written in, which the output is tokenised and quoted for too. Comments are kept, but any that
+/// appear inside a statement are moved to the line above it, and any after a statement on the line
+/// it ends are kept at the end of it.
+pub fn format(
+    src: &str,
+    dialect: &dyn Dialect,
+    options: FormatOptions,
+) -> Result<String, ParserError> {
+    let _style = DisplayStyleGuard::set(dialect);
+    let statements = Parser::new(src, dialect)?.parse()?;
+    let mut comments = Tokeniser::new(src, dialect)
+        .preserve_comments(true)
+        .collect_with_span()?
+        .into_iter()
+        .filter_map(|TokenWithSpan(token, span)| match token {
+            Token::Comment(comment) => Some((comment, span)),
+            _ => None,
+        })
+        .peekable();
+
+    let mut out = String::new();
+    for statement in statements {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+
+        while let Some((comment, _)) = comments.next_if(|(_, span)| span.start < statement.span.end)
+        {
+            out.push_str(&comment);
+            out.push('\n');
+        }
+
+        let mut printer = Printer { options, dialect, lines: Vec::new() };
+        printer.statement(0, &statement);
+        out.push_str(&printer.finish());
+        out.push(';');
+
+        let line = statement.span.end.line;
+        while let Some((comment, _)) = comments.next_if(|(_, span)| span.start.line == line) {
+            out.push(' ');
+            out.push_str(&comment);
+        }
+        out.push('\n');
+    }
+
+    for (comment, _) in comments {
+        out.push_str(&comment);
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+struct Printer<'a> {
+    options: FormatOptions,
+    dialect: &'a dyn Dialect,
+    lines: Vec<String>,
+}
+
+impl Printer<'_> {
+    fn finish(self) -> String {
+        let sql = self.lines.join("\n");
+        match self.options.keyword_case {
+            KeywordCase::Upper => sql,
+            KeywordCase::Lower => lowercase_keywords(sql, self.dialect),
+        }
+    }
+
+    fn statement(&mut self, depth: usize, statement: &Statement) {
+        match &statement.kind {
+            StatementKind::Select(select) => self.select(depth, select),
+            StatementKind::Insert(insert) => self.insert(depth, insert),
+            StatementKind::Update(update) => self.update(depth, update),
+            StatementKind::Delete(delete) => self.delete(depth, delete),
+            StatementKind::Create(create) => self.create(depth, create),
+            StatementKind::CreateIndex(create) => self.line(depth, create.to_string()),
+            StatementKind::CreateView(create) => self.create_view(depth, create),
+            StatementKind::AlterTable(alter) => self.alter_table(depth, alter),
+            StatementKind::Drop(drop) => self.line(depth, drop.to_string()),
+            StatementKind::Truncate(truncate) => self.line(depth, truncate.to_string()),
+        }
+    }
+
+    fn select(&mut self, depth: usize, select: &Select) {
+        self.query(depth, &select.body);
+
+        if !select.order.is_empty() {
+            self.list(depth, "ORDER BY", &strings(&select.order), false);
+        }
+        if let Some(limit) = &select.limit {
+            self.line(depth, format!("LIMIT {limit}"));
+        }
+        if let Some(offset) = &select.offset {
+            self.line(depth, format!("OFFSET {offset}"));
+        }
+    }
+
+    fn query(&mut self, depth: usize, query: &Query) {
+        let items = self.select_items(&query.projection);
+        self.list(depth, "SELECT", &items, self.options.one_item_per_line);
+
+        self.line(depth, format!("FROM {}", query.from));
+        for join in &query.joins {
+            self.join(depth + 1, join);
+        }
+
+        if let Some(filter) = &query.filter {
+            self.condition(depth, "WHERE", filter);
+        }
+        if !query.group.is_empty() {
+            self.list(depth, "GROUP BY", &strings(&query.group), false);
+        }
+        if let Some(having) = &query.having {
+            self.condition(depth, "HAVING", having);
+        }
+    }
+
+    fn select_items(&self, items: &[SelectItem]) -> Vec<String> {
+        let mut width = 0;
+        if self.options.align_aliases && self.options.one_item_per_line {
+            for item in items {
+                if let SelectItemKind::AliasedExpr { expr, .. } = &item.kind {
+                    width = width.max(expr.to_string().chars().count());
+                }
+            }
+        }
+
+        items
+            .iter()
+            .map(|item| match &item.kind {
+                SelectItemKind::AliasedExpr { expr, .. } => {
+                    let (expr, item) = (expr.to_string(), item.to_string());
+                    format!("{expr:width$}{}", &item[expr.len()..])
+                }
+                _ => item.to_string(),
+            })
+            .collect()
+    }
+
+    fn join(&mut self, depth: usize, join: &Join) {
+        // FROM t1, t2 stays on one line
+        if join.ty == JoinType::Comma {
+            if let Some(line) = self.lines.last_mut() {
+                line.push_str(&format!(", {}", join.from));
+            }
+            return;
+        }
+
+        let text = join.to_string();
+        match &join.constraint {
+            JoinConstraint::On(expr) if !self.fits(depth, &text) => {
+                let join = Join { constraint: JoinConstraint::None, ..join.clone() };
+                self.line(depth, join.to_string());
+                self.condition(depth + 1, "ON", expr);
+            }
+            _ => self.line(depth, text),
+        }
+    }
+
+    fn insert(&mut self, depth: usize, insert: &Insert) {
+        let text = insert.to_string();
+        if self.fits(depth, &text) {
+            return self.line(depth, text);
+        }
+
+        let mut head = format!("INSERT INTO {}", insert.table);
+        if !insert.columns.is_empty() {
+            let columns: Vec<_> = insert.columns.iter().map(|c| c.to_string()).collect();
+            head.push_str(&format!(" ({})", columns.join(", ")));
+        }
+        match &insert.source {
+            InsertSource::Values(rows) => {
+                self.line(depth, head);
+                let rows: Vec<_> =
+                    rows.iter().map(|row| format!("({})", strings(row).join(", "))).collect();
+                self.list(depth, "VALUES", &rows, true);
+            }
+            InsertSource::Select(select) => {
+                self.line(depth, head);
+                self.select(depth, select);
+            }
+            InsertSource::DefaultValues => self.line(depth, format!("{head} DEFAULT VALUES")),
+        }
+
+        match &insert.on_conflict {
+            Some(OnConflict::OnConflict {
+                columns,
+                action: ConflictAction::DoUpdate { set, filter },
+            }) => {
+                let mut head = String::from("ON CONFLICT");
+                if !columns.is_empty() {
+                    let columns: Vec<_> = columns.iter().map(|c| c.to_string()).collect();
+                    head.push_str(&format!(" ({})", columns.join(", ")));
+                }
+                self.line(depth, format!("{head} DO UPDATE"));
+                self.list(depth, "SET", &strings(set), self.options.one_item_per_line);
+                if let Some(filter) = filter {
+                    self.condition(depth, "WHERE", filter);
+                }
+            }
+            Some(OnConflict::DuplicateKeyUpdate(set)) => {
+                let head = "ON DUPLICATE KEY UPDATE";
+                self.list(depth, head, &strings(set), self.options.one_item_per_line);
+            }
+            Some(on_conflict) => self.line(depth, on_conflict.to_string()),
+            None => {}
+        }
+        self.returning(depth, &insert.returning);
+    }
+
+    fn update(&mut self, depth: usize, update: &Update) {
+        self.line(depth, format!("UPDATE {}", update.table));
+        self.list(depth, "SET", &strings(&update.set), self.options.one_item_per_line);
+        if let Some(filter) = &update.filter {
+            self.condition(depth, "WHERE", filter);
+        }
+        self.returning(depth, &update.returning);
+    }
+
+    fn delete(&mut self, depth: usize, delete: &Delete) {
+        self.line(depth, format!("DELETE FROM {}", delete.table));
+        if let Some(filter) = &delete.filter {
+            self.condition(depth, "WHERE", filter);
+        }
+        self.returning(depth, &delete.returning);
+    }
+
+    fn returning(&mut self, depth: usize, items: &[SelectItem]) {
+        if !items.is_empty() {
+            let items = self.select_items(items);
+            self.list(depth, "RETURNING", &items, self.options.one_item_per_line);
+        }
+    }
+
+    fn create(&mut self, depth: usize, create: &Create) {
+        let if_not_exists = if create.if_not_exists { "IF NOT EXISTS " } else { "" };
+        self.line(depth, format!("CREATE TABLE {if_not_exists}{} (", create.name));
+
+        let items: Vec<_> =
+            strings(&create.columns).into_iter().chain(strings(&create.constraints)).collect();
+        for (i, item) in items.iter().enumerate() {
+            let comma = if i + 1 < items.len() { "," } else { "" };
+            self.line(depth + 1, format!("{item}{comma}"));
+        }
+        self.line(depth, ")");
+    }
+
+    fn create_view(&mut self, depth: usize, create: &CreateView) {
+        let or_replace = if create.or_replace { "OR REPLACE " } else { "" };
+        let mut head = format!("CREATE {or_replace}VIEW {}", create.name);
+        if !create.columns.is_empty() {
+            let columns: Vec<_> = create.columns.iter().map(|c| c.to_string()).collect();
+            head.push_str(&format!(" ({})", columns.join(", ")));
+        }
+        self.line(depth, format!("{head} AS"));
+        self.select(depth, &create.query);
+    }
+
+    fn alter_table(&mut self, depth: usize, alter: &AlterTable) {
+        let if_exists = if alter.if_exists { "IF EXISTS " } else { "" };
+        let head = format!("ALTER TABLE {if_exists}{}", alter.name);
+        self.list(depth, &head, &strings(&alter.operations), true);
+    }
+
+    // Writes `head` followed by `items`, on one line if they fit, otherwise on indented lines
+    // below it, either packed up to the max width or one per line
+    fn list(&mut self, depth: usize, head: &str, items: &[String], one_per_line: bool) {
+        let text = format!("{head} {}", items.join(", "));
+        if (items.len() == 1 || !one_per_line) && self.fits(depth, &text) {
+            return self.line(depth, text);
+        }
+
+        self.line(depth, head);
+        let mut line = String::new();
+        for (i, item) in items.iter().enumerate() {
+            let comma = if i + 1 < items.len() { "," } else { "" };
+            let item = format!("{item}{comma}");
+            if one_per_line {
+                self.line(depth + 1, item);
+                continue;
+            }
+
+            if !line.is_empty() && !self.fits(depth + 1, &format!("{line} {item}")) {
+                self.line(depth + 1, std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&item);
+        }
+
+        if !line.is_empty() {
+            self.line(depth + 1, line);
+        }
+    }
+
+    // Writes `head` followed by `expr`, breaking before each top level AND/OR if it doesn't fit
+    fn condition(&mut self, depth: usize, head: &str, expr: &Expr) {
+        let text = format!("{head} {expr}");
+        if self.fits(depth, &text) {
+            return self.line(depth, text);
+        }
+
+        self.line(depth, head);
+        match &expr.kind {
+            ExprKind::BinaryOp { op: op @ (Op::And | Op::Or), .. } => {
+                let mut terms = Vec::new();
+                chain(expr, op, &mut terms);
+                for (i, term) in terms.iter().enumerate() {
+                    match i {
+                        0 => self.line(depth + 1, term),
+                        _ => self.line(depth + 1, format!("{op} {term}")),
+                    }
+                }
+            }
+            _ => self.line(depth + 1, expr.to_string()),
+        }
+    }
+
+    fn fits(&self, depth: usize, text: &str) -> bool {
+        depth * self.options.indent + text.chars().count() <= self.options.max_width
+    }
+
+    fn line(&mut self, depth: usize, text: impl AsRef<str>) {
+        let indent = " ".repeat(depth * self.options.indent);
+        self.lines.push(format!("{indent}{}", text.as_ref()));
+    }
+}
+
+fn strings<T: ToString>(items: &[T]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+// Flattens a left nested chain of `op` into its operands, parenthesised as they would be printed
+fn chain(expr: &Expr, op: &Op, terms: &mut Vec<String>) {
+    match &expr.kind {
+        ExprKind::BinaryOp { left, op: o, right } if o == op => {
+            chain(left, op, terms);
+            terms.push(operand(right, op.precedence() + 1));
+        }
+        _ => terms.push(operand(expr, op.precedence())),
+    }
+}
+
+fn operand(expr: &Expr, min: u8) -> String {
+    if expr.precedence() < min {
+        format!("({expr})")
+    } else {
+        expr.to_string()
+    }
+}
+
+// The AST prints keywords in upper case, so find them by tokenising the output
+fn lowercase_keywords(sql: String, dialect: &dyn Dialect) -> String {
+    let Ok(tokens) = Tokeniser::new(&sql, dialect).collect_with_span() else {
+        return sql;
+    };
+
+    let mut lines: Vec<Vec<char>> = sql.split('\n').map(|line| line.chars().collect()).collect();
+    for TokenWithSpan(token, Span { start, end }) in tokens {
+        if let Token::Keyword(_) = token {
+            let line = &mut lines[start.line as usize];
+            for c in &mut line[start.col as usize..end.col as usize] {
+                *c = c.to_ascii_lowercase();
+            }
+        }
+    }
+
+    lines.into_iter().map(String::from_iter).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dialect::{Dialect, GenericDialect, MySqlDialect, PostgresDialect},
+        parser::Parser,
+    };
+
+    use super::{format, FormatOptions, KeywordCase};
+
+    #[test]
+    fn test_format_select() {
+        let input = "
+            select a, b + 1 as bee, count(*) as n from t1 join t2 on t1.id = t2.id
+            left join t3 using (id), t4 where a = 1 and b in (1, 2) group by a, b
+            having count(*) > 1 order by a desc limit 10 offset 5";
+
+        let want = "\
+SELECT
+    a,
+    b + 1    AS bee,
+    count(*) AS n
+FROM t1
+    JOIN t2 ON t1.id = t2.id
+    LEFT JOIN t3 USING (id), t4
+WHERE a = 1 AND b IN (1, 2)
+GROUP BY a, b
+HAVING count(*) > 1
+ORDER BY a DESC
+LIMIT 10
+OFFSET 5;
+";
+        assert_eq!(want, format(input, &GenericDialect, FormatOptions::default()).unwrap());
+    }
+
+    #[test]
+    fn test_format_ddl() {
+        let input = "
+            alter table t1 add column c1 int not null default 0, drop column c2,
+            rename column c3 to c4; drop table if exists t1; truncate t2;
+            create unique index i1 on t1 (c1 desc, c2) where c3 > 0;
+            create or replace view v1 (a) as select c1 from t1 where c2 = 1 order by c1;
+            insert into t1 (c1, c2) values (1, 'aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa')
+            on conflict (c1) do update set c2 = excluded.c2 where t1.c2 <> '' returning *;
+            delete from t1 where c1 = 1 returning c1, c2";
+
+        let want = "\
+ALTER TABLE t1
+    ADD COLUMN c1 INT NOT NULL DEFAULT 0,
+    DROP COLUMN c2,
+    RENAME COLUMN c3 TO c4;
+
+DROP TABLE IF EXISTS t1;
+
+TRUNCATE TABLE t2;
+
+CREATE UNIQUE INDEX i1 ON t1 (c1 DESC, c2) WHERE c3 > 0;
+
+CREATE OR REPLACE VIEW v1 (a) AS
+SELECT c1
+FROM t1
+WHERE c2 = 1
+ORDER BY c1;
+
+INSERT INTO t1 (c1, c2)
+VALUES (1, 'aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa')
+ON CONFLICT (c1) DO UPDATE
+SET c2 = excluded.c2
+WHERE t1.c2 <> ''
+RETURNING *;
+
+DELETE FROM t1
+WHERE c1 = 1
+RETURNING
+    c1,
+    c2;
+";
+        assert_eq!(want, format(input, &GenericDialect, FormatOptions::default()).unwrap());
+    }
+
+    #[test]
+    fn test_format_options() {
+        let input = "select a, b as bee from t1 where a = 1 and (b = 2 or c = 3) and d is null";
+        let options = FormatOptions {
+            keyword_case: KeywordCase::Lower,
+            max_width: 30,
+            indent: 2,
+            one_item_per_line: false,
+            align_aliases: true,
+        };
+
+        let want = "\
+select a, b as bee
+from t1
+where
+  a = 1
+  and (b = 2 or c = 3)
+  and d is null;
+";
+        assert_eq!(want, format(input, &GenericDialect, options).unwrap());
+    }
+
+    #[test]
+    fn test_format_wraps_long_lists() {
+        let input = "
+            select * from t1 group by aaaaaaaa, bbbbbbbb, cccccccc, dddddddd, eeeeeeee, ffffffff,
+            gggggggg, hhhhhhhh, iiiiiiii;
+            update t1 set a = 1, b = 'two' where c = 3;
+            insert into t1 values (1, 'aaaaaaaaaaaa'), (2, 'bbbbbbbbbbbb'), (3, 'cccccccccccccccc');
+            create table t1 (c1 int, c2 varchar(255));
+            delete from t1";
+
+        let want = "\
+SELECT *
+FROM t1
+GROUP BY
+    aaaaaaaa, bbbbbbbb, cccccccc, dddddddd, eeeeeeee, ffffffff, gggggggg,
+    hhhhhhhh, iiiiiiii;
+
+UPDATE t1
+SET
+    a = 1,
+    b = 'two'
+WHERE c = 3;
+
+INSERT INTO t1
+VALUES
+    (1, 'aaaaaaaaaaaa'),
+    (2, 'bbbbbbbbbbbb'),
+    (3, 'cccccccccccccccc');
+
+CREATE TABLE t1 (
+    c1 INT,
+    c2 VARCHAR(255)
+);
+
+DELETE FROM t1;
+";
+        assert_eq!(want, format(input, &GenericDialect, FormatOptions::default()).unwrap());
+    }
+
+    #[test]
+    fn test_format_preserves_comments() {
+        let input = "
+            -- first
+            select a, /* inline */ b from t1; # hash
+            /* before
+               second */
+            delete from t1 -- trailing
+            -- end";
+
+        let want = "\
+-- first
+/* inline */
+SELECT
+    a,
+    b
+FROM t1; # hash
+
+/* before
+               second */
+DELETE FROM t1; -- trailing
+-- end
+";
+        assert_eq!(want, format(input, &GenericDialect, FormatOptions::default()).unwrap());
+    }
+
+    #[test]
+    fn test_format_dialect() {
+        let input = "select `a b`, `from`, 'c\\\\d' from t1 where `a b` = 1 # done";
+        let options = FormatOptions { keyword_case: KeywordCase::Lower, ..Default::default() };
+
+        let want = "\
+select
+    `a b`,
+    `from`,
+    'c\\\\d'
+from t1
+where `a b` = 1; # done
+";
+        assert_eq!(want, format(input, &MySqlDialect, options).unwrap());
+    }
+
+    #[test]
+    fn test_format_keeps_meaning() {
+        let cases: [(&dyn Dialect, &str, &str); 2] = [
+            (
+                &PostgresDialect,
+                "select \"UserId\", UserId as \"Name\" from \"Users\" where \"Users\".\"Id\" = 1",
+                "\
+SELECT
+    \"UserId\",
+    UserId AS \"Name\"
+FROM \"Users\"
+WHERE \"Users\".\"Id\" = 1;
+",
+            ),
+            (
+                &MySqlDialect,
+                "select replace(a, 'x', 'y'), date(b), `Count`(c) from t1",
+                "\
+SELECT
+    replace(a, 'x', 'y'),
+    date(b),
+    `Count`(c)
+FROM t1;
+",
+            ),
+        ];
+
+        for (dialect, input, want) in cases {
+            let have = format(input, dialect, FormatOptions::default()).unwrap();
+            assert_eq!(want, have);
+
+            let want = Parser::new(input, dialect).unwrap().parse().unwrap();
+            assert_eq!(want, Parser::new(&have, dialect).unwrap().parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_format_is_stable() {
+        let inputs = [
+            "select \"select\", 'it''s' as \"a b\" from t1 natural join t2 where not a or b",
+            "select * from t1 cross join lateral (select * from t2) t2 order by a nulls first",
+            "-- c\nselect count(distinct a) filter (where b) from t1 group by rollup (a, (b, c))",
+            "select a from t1; -- c\nselect b /* d */ from t2 # e",
+            "create table t1 (primary key (c1), constraint u1 unique (c2))",
+        ];
+
+        for input in inputs {
+            for keyword_case in [KeywordCase::Upper, KeywordCase::Lower] {
+                for one_item_per_line in [true, false] {
+                    let options = FormatOptions {
+                        keyword_case,
+                        one_item_per_line,
+                        max_width: 20,
+                        ..Default::default()
+                    };
+
+                    let once = format(input, &GenericDialect, options).unwrap();
+                    assert_eq!(once, format(&once, &GenericDialect, options).unwrap());
+
+                    let want = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+                    let have = Parser::new(&once, &GenericDialect).unwrap().parse().unwrap();
+                    assert_eq!(want, have, "{once}");
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,23 @@
+pub mod ast;
+pub mod dialect;
+pub mod format;
 pub mod parser;
+pub mod stream;
 mod tokeniser;
+pub mod visitor;
 
+pub use ast::{
+    AlterColumnOperation, AlterTable, AlterTableOperation, ColumnConstraint, ColumnConstraintKind,
+    ColumnDef, ColumnType, ConflictAction, Create, CreateIndex, CreateView, Decimal, Delete,
+    DialectDisplay, Drop, Expr, ExprKind, FromTable, FromTableKind, Ident, Insert, InsertSource,
+    Join, JoinConstraint, JoinType, Name, ObjectType, OnConflict, Op, OrderByExpr, Query,
+    References, ReferentialAction, Select, SelectItem, SelectItemKind, Set, Statement,
+    StatementKind, TableConstraint, TableConstraintKind, Truncate, UnaryOp, Update, Value,
+};
+pub use format::{format, FormatOptions, KeywordCase};
+pub use parser::{Expected, NumberMode, Parser, ParserError};
+pub use stream::StatementStream;
+pub use tokeniser::{
+    Keyword, Location, QuoteStyle, Span, Token, TokenWithSpan, Tokeniser, TokeniserError,
+};
+pub use visitor::{Visit, VisitMut, Visitor, VisitorMut};
@@ -1,352 +1,633 @@
-use crate::tokeniser::{Keyword, Location, Token, TokenWithLocation, Tokeniser};
-
-#[derive(PartialEq, Debug)]
-pub enum Statement {
-    Select(Select),
-    Insert(Insert),
-    Update(Update),
-    Delete(Delete),
-    Create(Create),
-}
-
-#[derive(PartialEq, Debug)]
-enum Value {
-    Number(String),
-    String(String),
-    Bool(bool),
-    Null,
+use crate::{
+    ast::{
+        AlterColumnOperation, AlterTable, AlterTableOperation, ColumnConstraint,
+        ColumnConstraintKind, ColumnDef, ColumnType, ConflictAction, Create, CreateIndex,
+        CreateView, Decimal, Delete, Drop, Expr, ExprKind, FromTable, FromTableKind, Ident, Insert,
+        InsertSource, Join, JoinConstraint, JoinType, Name, ObjectType, OnConflict, Op,
+        OrderByExpr, Query, References, ReferentialAction, Select, SelectItem, SelectItemKind, Set,
+        Statement, StatementKind, TableConstraint, TableConstraintKind, Truncate, UnaryOp, Update,
+        Value,
+    },
+    dialect::{self, Dialect},
+    tokeniser::{Keyword, Location, Span, Token, TokenWithSpan, Tokeniser, TokeniserError},
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParserError {
+    Tokeniser(TokeniserError),
+    Io { kind: std::io::ErrorKind, message: String, location: Location },
+    Unexpected { token: Token<'static>, span: Span, expected: Vec<Expected> },
+    RecursionLimit { span: Span },
+    NumberOutOfRange { literal: String, span: Span },
 }
 
-#[derive(PartialEq, Debug)]
-enum Op {
-    Eq,
-    Neq,
-    Lt,
-    Le,
-    Gt,
-    Ge,
-    And,
-    Or,
+/// Something the parser would have accepted in place of an unexpected token
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expected {
+    Token(Token<'static>),
+    Identifier,
+    Expression,
 }
 
-#[derive(PartialEq, Debug)]
-enum Ident {
-    Single(String),
-    Compound(Vec<String>),
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "`{token}`"),
+            Expected::Identifier => write!(f, "identifier"),
+            Expected::Expression => write!(f, "expression"),
+        }
+    }
 }
 
-#[derive(PartialEq, Debug)]
-enum Expr {
-    Ident(Ident),
-    Wildcard,
-    QualifiedWildcard(Vec<String>),
-    Value(Value),
-    IsNull(Box<Expr>),
-    IsNotNull(Box<Expr>),
-    InList { expr: Box<Expr>, list: Vec<Expr>, negated: bool },
-    Between { expr: Box<Expr>, negated: bool, low: Box<Expr>, high: Box<Expr> },
-    BinaryOp { left: Box<Expr>, op: Op, right: Box<Expr> },
-    SubQuery(Box<Query>),
-    // TODO: UnaryOp
-    // TODO: functions
-}
+impl ParserError {
+    pub fn span(&self) -> Span {
+        match self {
+            ParserError::Tokeniser(e) => Span { start: e.location(), end: e.location() },
+            ParserError::Io { location, .. } => Span { start: *location, end: *location },
+            ParserError::Unexpected { span, .. }
+            | ParserError::RecursionLimit { span }
+            | ParserError::NumberOutOfRange { span, .. } => *span,
+        }
+    }
 
-#[derive(PartialEq, Debug)]
-struct Query {
-    projection: Vec<SelectItem>,
-    from: FromTable,
-    joins: Vec<Join>,
-    filter: Option<Expr>,
-    group: Vec<Expr>,
-}
+    /// The error message without the location
+    pub fn message(&self) -> String {
+        match self {
+            ParserError::Tokeniser(e) => {
+                let message = e.to_string();
+                let location = format!("{}: ", e.location());
+                message.strip_prefix(&location).unwrap_or(&message).to_owned()
+            }
+            ParserError::Unexpected { token, expected, .. } => {
+                let mut message = match token {
+                    Token::Eof => format!("unexpected {token}"),
+                    _ => format!("unexpected `{token}`"),
+                };
+                if let [expected] = expected.as_slice() {
+                    message.push_str(&format!(", expected {expected}"));
+                } else if !expected.is_empty() {
+                    let expected: Vec<_> = expected.iter().map(|e| e.to_string()).collect();
+                    message.push_str(&format!(", expected one of: {}", expected.join(", ")));
+                }
+                message
+            }
+            ParserError::Io { message, .. } => message.clone(),
+            ParserError::RecursionLimit { .. } => "recursion limit exceeded".into(),
+            ParserError::NumberOutOfRange { literal, .. } => {
+                format!("number out of range: {literal}")
+            }
+        }
+    }
 
-#[derive(PartialEq, Debug)]
-enum FromTable {
-    Table { name: Vec<String>, alias: Option<String> },
-    Derived { query: Box<Query>, alias: Option<String> },
-}
+    /// Render the error with the line of `src` it occurred on, underlining the error span
+    pub fn render(&self, src: &str) -> String {
+        let Span { start, end } = self.span();
+        let line = src.lines().nth(start.line as usize).unwrap_or_default();
 
-#[derive(PartialEq, Debug)]
-enum JoinConstraint {
-    On(Expr),
-    Using(Vec<String>),
-}
+        let width = if end.line == start.line {
+            end.col.saturating_sub(start.col)
+        } else {
+            (line.chars().count() as u64).saturating_sub(start.col)
+        };
 
-#[derive(PartialEq, Debug)]
-enum JoinType {
-    Inner,
-    // TODO: add more joins
+        let lineno = (start.line + 1).to_string();
+        let gutter = " ".repeat(lineno.len());
+        format!(
+            "error: {}\n{gutter}--> {start}\n{gutter} |\n{lineno} | {line}\n{gutter} | {}{}\n",
+            self.message(),
+            " ".repeat(start.col as usize),
+            "^".repeat(width.max(1) as usize),
+        )
+    }
 }
 
-#[derive(PartialEq, Debug)]
-struct Join {
-    from: FromTable,
-    ty: JoinType,
-    constraint: JoinConstraint,
+impl std::fmt::Display for ParserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.span().start, self.message())
+    }
 }
 
-#[derive(PartialEq, Debug)]
-struct OrderByExpr {
-    expr: Expr,
-    desc: bool, // Default is false/ASC
-}
+impl std::error::Error for ParserError {}
 
-#[derive(PartialEq, Debug)]
-enum SelectItem {
-    Expr(Expr),
-    AliasedExpr { expr: Expr, alias: String },
-    QualifiedWildcard(Vec<String>),
-    Wildcard,
+impl From<TokeniserError> for ParserError {
+    fn from(e: TokeniserError) -> Self {
+        Self::Tokeniser(e)
+    }
 }
 
-#[derive(PartialEq, Debug)]
-pub struct Select {
-    body: Query,
-    order: OrderByExpr,
-    limit: Expr,
-}
+pub type Result<T> = std::result::Result<T, ParserError>;
 
-#[derive(PartialEq, Debug)]
-pub struct Insert {
-    table: Ident,
-    rows: Vec<Vec<Expr>>,
+/// The default [`Parser::recursion_limit`]
+const DEFAULT_RECURSION_LIMIT: usize = 128;
+
+const STATEMENT_KEYWORDS: [Keyword; 8] = [
+    Keyword::Select,
+    Keyword::Insert,
+    Keyword::Update,
+    Keyword::Delete,
+    Keyword::Create,
+    Keyword::Alter,
+    Keyword::Drop,
+    Keyword::Truncate,
+];
+
+/// How the parser represents number literals
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum NumberMode {
+    /// Keep the literal's text in a `Value::Number`
+    #[default]
+    Text,
+    /// Integers become `Value::Int` and anything else `Value::Float`
+    Float,
+    /// Integers become `Value::Int` and anything else an exact `Value::Decimal`
+    Decimal,
 }
 
-#[derive(PartialEq, Debug)]
-pub struct Update {
-    table: Ident,
-    set: Vec<Set>,
-    filter: Option<Expr>,
+pub struct Parser<'a> {
+    tokens: Vec<TokenWithSpan<'a>>,
+    // The source the tokens were read from, and the offset it starts at in their spans
+    src: &'a str,
+    src_offset: usize,
+    index: usize,
+    depth: usize,
+    recursion_limit: usize,
+    dialect: &'a dyn Dialect,
+    number_mode: NumberMode,
+    // What would have been accepted at the furthest token index that failed to match
+    expected: Vec<Expected>,
+    expected_index: usize,
 }
 
-#[derive(PartialEq, Debug)]
-pub struct Set {
-    column: Ident,
-    expr: Expr,
-}
+impl<'a> Parser<'a> {
+    pub fn new(src: &'a str, dialect: &'a dyn Dialect) -> Result<Self> {
+        let tokens = Tokeniser::new(src, dialect).collect_with_span()?;
+        Ok(Self::from_tokens(tokens, src, 0, dialect))
+    }
 
-#[derive(PartialEq, Debug)]
-pub struct Delete {
-    table: Ident,
-    filter: Option<Expr>,
-}
+    // `tokens` must end with Eof and be read from `src`, which starts at `src_offset` in the spans
+    pub(crate) fn from_tokens(
+        tokens: Vec<TokenWithSpan<'a>>,
+        src: &'a str,
+        src_offset: usize,
+        dialect: &'a dyn Dialect,
+    ) -> Self {
+        Self {
+            tokens,
+            src,
+            src_offset,
+            index: 0,
+            depth: 0,
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+            dialect,
+            number_mode: NumberMode::default(),
+            expected: vec![],
+            expected_index: 0,
+        }
+    }
 
-#[derive(PartialEq, Debug)]
-pub struct Create {
-    name: String,
-    columns: Vec<ColumnDef>,
-}
+    /// Sets how number literals are represented, numbers that don't fit their type are an error
+    pub fn number_mode(mut self, mode: NumberMode) -> Self {
+        self.number_mode = mode;
+        self
+    }
 
-#[derive(PartialEq, Debug)]
-enum ColumnType {
-    Int,
-    Varchar(u16),
-}
+    /// Sets the maximum nesting of expressions and queries, 128 by default which fits in a 2 MiB
+    /// thread stack. Each operator in a chain like `a + b + c` counts as a level, as it nests the
+    /// operands before it. Input nested any deeper returns [`ParserError::RecursionLimit`] instead
+    /// of overflowing the stack when it's parsed, displayed or dropped, so a higher limit may need
+    /// a bigger stack
+    pub fn recursion_limit(mut self, limit: usize) -> Self {
+        self.recursion_limit = limit;
+        self
+    }
 
-#[derive(PartialEq, Debug)]
-struct ColumnDef {
-    ty: ColumnType,
-    name: String,
-    // TODO: constraints
-}
+    pub fn parse(&mut self) -> Result<Vec<Statement>> {
+        let mut statements = Vec::new();
+        while let Some(statement) = self.parse_statement()? {
+            statements.push(statement);
+        }
 
-#[derive(Debug)]
-pub enum ParserError {
-    TokeniserError(String),
-    Unexpected(String),
-}
+        Ok(statements)
+    }
 
-struct Unexpected<'a>(&'a Token, &'a Location);
+    /// Parses every statement, collecting errors instead of stopping at the first one. After an
+    /// error the parser skips ahead to the next `;`, or to a statement keyword starting a line that
+    /// isn't indented further than the statement with the error, and carries on.
+    pub fn parse_with_recovery(&mut self) -> (Vec<Statement>, Vec<ParserError>) {
+        let mut statements = Vec::new();
+        let mut errors = Vec::new();
+        loop {
+            let start = self.index;
+            match self.parse_statement() {
+                Ok(Some(statement)) => statements.push(statement),
+                Ok(None) => break,
+                Err(e) => {
+                    self.synchronise(start, e.span());
+                    errors.push(e);
+                }
+            }
+        }
 
-impl<'a> std::fmt::Display for Unexpected<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: unexpected token {:?}", self.1, self.0)
+        (statements, errors)
     }
-}
 
-impl<'a> From<Unexpected<'a>> for ParserError {
-    fn from(value: Unexpected<'a>) -> Self {
-        Self::Unexpected(value.to_string())
+    // Parses the next statement, skipping any empty statements, returns None at EOF
+    fn parse_statement(&mut self) -> Result<Option<Statement>> {
+        let (kind, start) = loop {
+            let start = self.start();
+            let kind = match self.peek().0 {
+                Token::Keyword(kw) if !self.dialect.supports_statement(kw) => {
+                    Err(self.unexpected_statement())?
+                }
+                Token::Keyword(kw) => match kw {
+                    Keyword::Select => StatementKind::Select(self.parse_select()?),
+                    Keyword::Insert => StatementKind::Insert(self.parse_insert()?),
+                    Keyword::Update => StatementKind::Update(self.parse_update()?),
+                    Keyword::Delete => StatementKind::Delete(self.parse_delete()?),
+                    Keyword::Create => self.parse_create()?,
+                    Keyword::Alter => StatementKind::AlterTable(self.parse_alter_table()?),
+                    Keyword::Drop => StatementKind::Drop(self.parse_drop()?),
+                    Keyword::Truncate => StatementKind::Truncate(self.parse_truncate()?),
+                    _ => Err(self.unexpected_statement())?,
+                },
+                Token::Semicolon => {
+                    self.next();
+                    continue;
+                }
+                Token::Eof => return Ok(None),
+                _ => Err(self.unexpected_statement())?,
+            };
+
+            break (kind, start);
+        };
+
+        Ok(Some(Statement::new(kind, self.span_from(start))))
     }
-}
 
-pub type Result<T> = std::result::Result<T, ParserError>;
+    // Moves past the error at `span` to where the next statement starts, always making progress
+    // from `start` so a statement that fails on its first token can't be retried forever. Statement
+    // keywords inside a statement, e.g. the DROP in `ALTER TABLE t1 DROP c1`, are skipped by only
+    // stopping at ones that start a line, no further indented than the statement with the error
+    fn synchronise(&mut self, start: usize, span: Span) {
+        // The statement with the error can have been cut short by an earlier statement ending, so
+        // its indentation is taken from the last statement keyword starting a line before the error
+        let first =
+            start + self.tokens[start..].iter().take_while(|t| t.0 == Token::Semicolon).count();
+        let col = (0..=first)
+            .rev()
+            .take_while(|&i| i == first || self.tokens[i].0 != Token::Semicolon)
+            .find(|&i| self.starts_statement(i))
+            .map_or(self.get(first).1.start.col, |i| self.tokens[i].1.start.col);
+
+        self.index = self.tokens[start..]
+            .iter()
+            .position(|TokenWithSpan(_, s)| *s == span)
+            .map_or(self.index, |i| start + i)
+            .max(start + 1);
 
-pub struct Parser {
-    tokens: Vec<TokenWithLocation>,
-    index: usize,
-}
+        loop {
+            match self.peek() {
+                TokenWithSpan(Token::Semicolon | Token::Eof, _) => break,
+                TokenWithSpan(_, span)
+                    if self.starts_statement(self.index) && span.start.col <= col =>
+                {
+                    break
+                }
+                _ => self.next(),
+            };
+        }
+    }
 
-impl Parser {
-    pub fn new(src: &str) -> Result<Self> {
-        Tokeniser::new(src)
-            .collect_with_location()
-            .map_err(|e| ParserError::TokeniserError(e.to_string()))
-            .map(|tokens| Self { tokens, index: 0 })
+    // Whether the token at `i` is a statement keyword at the start of a line
+    fn starts_statement(&self, i: usize) -> bool {
+        let TokenWithSpan(token, span) = self.get(i);
+        let starts_line = i == 0 || self.tokens[i - 1].1.end.line < span.start.line;
+        match token {
+            Token::Keyword(kw) => {
+                starts_line
+                    && STATEMENT_KEYWORDS.contains(kw)
+                    && self.dialect.supports_statement(*kw)
+            }
+            _ => false,
+        }
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Statement>> {
-        let mut statements = Vec::new();
-        loop {
-            let TokenWithLocation(token, location) = self.peek();
-            statements.push(match token {
-                Token::Keyword(kw) => match kw {
-                    Keyword::Select => Statement::Select(self.parse_select()?),
-                    Keyword::Insert => Statement::Insert(self.parse_insert()?),
-                    Keyword::Update => Statement::Update(self.parse_update()?),
-                    Keyword::Delete => Statement::Delete(self.parse_delete()?),
-                    Keyword::Create => Statement::Create(self.parse_create()?),
-                    _ => Err(Unexpected(&token, &location))?,
-                },
-                Token::Semicolon => continue,
-                Token::Eof => break,
-                _ => Err(Unexpected(&token, &location))?,
-            });
+    fn unexpected_statement(&mut self) -> ParserError {
+        for kw in STATEMENT_KEYWORDS {
+            if self.dialect.supports_statement(kw) {
+                self.expect(Expected::Token(Token::Keyword(kw)));
+            }
         }
 
-        Ok(statements)
+        self.unexpected_next()
     }
 
     fn parse_select(&mut self) -> Result<Select> {
         let body = self.parse_query()?;
 
+        let mut order = Vec::new();
         if self.check_keywords(&[Keyword::Order, Keyword::By]) {
-            todo!()
+            while {
+                order.push(self.parse_order_by_expr()?);
+                self.check_tokens(&[Token::Comma])
+            } {}
         }
 
-        if self.check_keywords(&[Keyword::Limit]) {
-            todo!()
+        let (mut limit, mut offset) = (None, None);
+        loop {
+            if limit.is_none() && self.check_keywords(&[Keyword::Limit]) {
+                let expr = self.parse_expr(0)?;
+                // MySQL style LIMIT <offset>, <limit>
+                if offset.is_none() && self.check_tokens(&[Token::Comma]) {
+                    offset = Some(expr);
+                    limit = Some(self.parse_expr(0)?);
+                } else {
+                    limit = Some(expr);
+                }
+            } else if offset.is_none() && self.check_keywords(&[Keyword::Offset]) {
+                offset = Some(self.parse_expr(0)?);
+            } else {
+                break;
+            }
         }
 
-        Ok(Select { body, order: todo!(), limit: todo!() })
+        Ok(Select { body, order, limit, offset })
+    }
+
+    fn parse_order_by_expr(&mut self) -> Result<OrderByExpr> {
+        let expr = self.parse_expr(0)?;
+
+        let desc = if self.check_keywords(&[Keyword::Desc]) {
+            true
+        } else {
+            self.check_keywords(&[Keyword::Asc]);
+            false
+        };
+
+        let nulls_first = if self.check_keywords(&[Keyword::Nulls, Keyword::First]) {
+            Some(true)
+        } else if self.check_keywords(&[Keyword::Nulls, Keyword::Last]) {
+            Some(false)
+        } else {
+            None
+        };
+
+        Ok(OrderByExpr { expr, desc, nulls_first })
     }
 
     fn parse_query(&mut self) -> Result<Query> {
-        self.parse_keywords(&[Keyword::Select])?;
+        self.recurse(|parser| {
+            parser.parse_keywords(&[Keyword::Select])?;
 
-        let projection = self.parse_projection()?;
+            let projection = parser.parse_projection()?;
 
-        self.parse_keywords(&[Keyword::From])?;
-        let from = self.parse_from()?;
+            parser.parse_keywords(&[Keyword::From])?;
+            let from = parser.parse_from()?;
 
-        let joins = self.parse_joins()?;
+            let joins = parser.parse_joins()?;
 
-        let filter =
-            if self.check_keywords(&[Keyword::Where]) { Some(self.parse_expr(0)?) } else { None };
+            let filter = if parser.check_keywords(&[Keyword::Where]) {
+                Some(parser.parse_expr(0)?)
+            } else {
+                None
+            };
+
+            let mut group = Vec::new();
+            if parser.check_keywords(&[Keyword::Group, Keyword::By]) {
+                while {
+                    group.push(parser.parse_group_by_expr()?);
+                    parser.check_tokens(&[Token::Comma])
+                } {}
+            }
 
-        let group =
-            if self.check_keywords(&[Keyword::Group, Keyword::By]) { todo!() } else { vec![] };
+            let having = if parser.check_keywords(&[Keyword::Having]) {
+                Some(parser.parse_expr(0)?)
+            } else {
+                None
+            };
 
-        Ok(Query { projection, from, joins, filter, group })
+            Ok(Query { projection, from, joins, filter, group, having })
+        })
     }
 
-    fn parse_from(&mut self) -> Result<FromTable> {
-        let TokenWithLocation(token, location) = self.next();
-        let from = match token {
-            Token::Ident(a) => {
-                let mut name = vec![a];
-                if self.check_tokens(&[Token::Dot]) {
-                    let TokenWithLocation(token, location) = self.next();
-                    match token {
-                        Token::Ident(b) => name.push(b),
-                        _ => Err(Unexpected(&token, &location))?,
-                    }
-                }
+    fn parse_group_by_expr(&mut self) -> Result<Expr> {
+        let start = self.start();
+        let kind = if self.check_keywords(&[Keyword::Grouping, Keyword::Sets]) {
+            ExprKind::GroupingSets(self.parse_grouping_sets()?)
+        } else if self.check_keywords(&[Keyword::Rollup]) {
+            ExprKind::Rollup(self.parse_grouping_sets()?)
+        } else if self.check_keywords(&[Keyword::Cube]) {
+            ExprKind::Cube(self.parse_grouping_sets()?)
+        } else {
+            return self.parse_expr(0);
+        };
 
-                let mut alias = None;
-                if self.check_keywords(&[Keyword::As]) {
-                    let TokenWithLocation(token, location) = self.next();
-                    match token {
-                        Token::Ident(a) => alias = Some(a),
-                        _ => Err(Unexpected(&token, &location))?,
-                    }
-                }
+        Ok(Expr::new(kind, self.span_from(start)))
+    }
 
-                FromTable::Table { name, alias }
+    // Parses a parenthesised list of grouping sets, where each set is either a single expression
+    // or a parenthesised (possibly empty) list of expressions
+    fn parse_grouping_sets(&mut self) -> Result<Vec<Vec<Expr>>> {
+        let mut sets = Vec::new();
+
+        self.parse_tokens(&[Token::LParen])?;
+        while {
+            if self.check_tokens(&[Token::LParen]) {
+                let mut set = Vec::new();
+                if !self.check_tokens(&[Token::RParen]) {
+                    while {
+                        set.push(self.parse_expr(0)?);
+                        self.check_tokens(&[Token::Comma])
+                    } {}
+                    self.parse_tokens(&[Token::RParen])?;
+                }
+                sets.push(set);
+            } else {
+                sets.push(vec![self.parse_expr(0)?]);
             }
-            Token::LParen => {
-                let query = self.parse_query().map(Box::new)?;
-                self.parse_tokens(&[Token::RParen])?;
 
-                let alias = if let TokenWithLocation(Token::Ident(alias), _) = self.peek() {
-                    self.next();
-                    Some(alias)
-                } else {
-                    None
-                };
+            self.check_tokens(&[Token::Comma])
+        } {}
+        self.parse_tokens(&[Token::RParen])?;
 
-                FromTable::Derived { query, alias }
-            }
-            _ => Err(Unexpected(&token, &location))?,
+        Ok(sets)
+    }
+
+    fn parse_from(&mut self) -> Result<FromTable> {
+        let start = self.start();
+        let lateral = self.check_keywords(&[Keyword::Lateral]);
+
+        let kind = if lateral || self.peek().0 == Token::LParen {
+            self.parse_tokens(&[Token::LParen])?;
+            let query = self.parse_query().map(Box::new)?;
+            self.parse_tokens(&[Token::RParen])?;
+
+            let alias = if let TokenWithSpan(Token::Ident(alias), span) = self.peek() {
+                let alias = self.name(alias, *span);
+                self.next();
+                Some(alias)
+            } else {
+                None
+            };
+
+            FromTableKind::Derived { lateral, query, alias }
+        } else {
+            let name = self.parse_ident()?;
+
+            let alias = if self.check_keywords(&[Keyword::As]) {
+                Some(self.parse_identifier()?)
+            } else {
+                None
+            };
+
+            FromTableKind::Table { name, alias }
         };
 
-        Ok(from)
+        Ok(FromTable::new(kind, self.span_from(start)))
     }
 
     fn parse_joins(&mut self) -> Result<Vec<Join>> {
         let mut joins = Vec::new();
 
-        if !self.check_keywords(&[Keyword::Join]) {
-            return Ok(joins);
-        }
-
-        while {
-            let from = self.parse_from()?;
-            if self.check_keywords(&[Keyword::On]) {
-                let constraint = JoinConstraint::On(self.parse_expr(0)?);
-                let ty = JoinType::Inner;
-                joins.push(Join { from, ty, constraint })
-            } else if self.check_keywords(&[Keyword::Using]) {
-                let mut columns = Vec::new();
+        loop {
+            if self.check_tokens(&[Token::Comma]) {
+                let from = self.parse_from()?;
+                let (ty, constraint, span) = (JoinType::Comma, JoinConstraint::None, from.span);
+                joins.push(Join { from, ty, constraint, span });
+                continue;
+            }
 
-                self.parse_tokens(&[Token::LParen])?;
-                while {
-                    let TokenWithLocation(token, location) = self.next();
-                    match token {
-                        Token::Ident(a) => columns.push(a), //  TODO: parse properly
-                        _ => Err(Unexpected(&token, &location))?,
-                    };
+            let start = self.start();
+            let natural = self.check_keywords(&[Keyword::Natural]);
+            let ty = if self.check_keywords(&[Keyword::Join])
+                || self.check_keywords(&[Keyword::Inner, Keyword::Join])
+            {
+                JoinType::Inner
+            } else if self.check_keywords(&[Keyword::Left]) {
+                self.parse_outer_join()?;
+                JoinType::Left
+            } else if self.check_keywords(&[Keyword::Right]) {
+                self.parse_outer_join()?;
+                JoinType::Right
+            } else if self.check_keywords(&[Keyword::Full]) {
+                self.parse_outer_join()?;
+                JoinType::Full
+            } else if !natural && self.check_keywords(&[Keyword::Cross, Keyword::Join]) {
+                JoinType::Cross
+            } else if natural {
+                Err(self.unexpected_next())?
+            } else {
+                break;
+            };
 
-                    self.check_tokens(&[Token::Comma])
-                } {}
-                self.parse_tokens(&[Token::RParen])?;
+            let from = self.parse_from()?;
 
-                let constraint = JoinConstraint::Using(columns);
-                let ty = JoinType::Inner;
-                joins.push(Join { from, ty, constraint })
-            }
+            let constraint = if natural {
+                JoinConstraint::Natural
+            } else if ty == JoinType::Cross {
+                JoinConstraint::None
+            } else if self.check_keywords(&[Keyword::On]) {
+                JoinConstraint::On(self.parse_expr(0)?)
+            } else if self.check_keywords(&[Keyword::Using]) {
+                JoinConstraint::Using(self.parse_column_list()?)
+            } else {
+                Err(self.unexpected_next())?
+            };
 
-            self.check_keywords(&[Keyword::Join])
-        } {}
+            joins.push(Join { from, ty, constraint, span: self.span_from(start) })
+        }
 
         Ok(joins)
     }
 
+    // Parses the remainder of LEFT/RIGHT/FULL [OUTER] JOIN
+    fn parse_outer_join(&mut self) -> Result<()> {
+        self.check_keywords(&[Keyword::Outer]);
+        self.parse_keywords(&[Keyword::Join])
+    }
+
     fn parse_insert(&mut self) -> Result<Insert> {
         self.parse_keywords(&[Keyword::Insert, Keyword::Into])?;
 
-        let TokenWithLocation(token, location) = self.peek();
-        let Token::Ident(_) = token else { Err(Unexpected(&token, &location))? };
         let table = self.parse_ident()?;
+        let columns =
+            if self.peek().0 == Token::LParen { self.parse_column_list()? } else { Vec::new() };
 
-        self.parse_keywords(&[Keyword::Values])?;
-
-        self.parse_tokens(&[Token::LParen])?;
-        let mut rows = Vec::new();
-        while {
-            self.parse_tokens(&[Token::LParen])?;
-            let mut exprs = Vec::new();
+        let source = if self.check_keywords(&[Keyword::Default, Keyword::Values]) {
+            InsertSource::DefaultValues
+        } else if self.check_keywords(&[Keyword::Values]) {
+            let mut rows = Vec::new();
             while {
-                exprs.push(self.parse_expr(0)?);
+                self.parse_tokens(&[Token::LParen])?;
+                let mut exprs = Vec::new();
+                while {
+                    exprs.push(self.parse_insert_value()?);
+                    self.check_tokens(&[Token::Comma])
+                } {}
+                rows.push(exprs);
+                self.parse_tokens(&[Token::RParen])?;
                 self.check_tokens(&[Token::Comma])
             } {}
-            rows.push(exprs);
-            self.parse_tokens(&[Token::RParen])?;
-            self.check_tokens(&[Token::Comma])
-        } {}
-        self.parse_tokens(&[Token::RParen])?;
+            InsertSource::Values(rows)
+        } else if self.peek().0 == Token::Keyword(Keyword::Select) {
+            InsertSource::Select(self.parse_select().map(Box::new)?)
+        } else {
+            self.expect(Expected::Token(Token::Keyword(Keyword::Select)));
+            Err(self.unexpected_next())?
+        };
+
+        let on_conflict = self.parse_on_conflict()?;
+        let returning = self.parse_returning()?;
+
+        Ok(Insert { table, columns, source, on_conflict, returning })
+    }
+
+    // Parses an optional `ON CONFLICT` or `ON DUPLICATE KEY UPDATE` clause
+    fn parse_on_conflict(&mut self) -> Result<Option<OnConflict>> {
+        if self.check_keywords(&[Keyword::On, Keyword::Duplicate, Keyword::Key, Keyword::Update]) {
+            return Ok(Some(OnConflict::DuplicateKeyUpdate(self.parse_assignments()?)));
+        }
+        if !self.check_keywords(&[Keyword::On, Keyword::Conflict]) {
+            return Ok(None);
+        }
+
+        let columns =
+            if self.peek().0 == Token::LParen { self.parse_column_list()? } else { Vec::new() };
 
-        Ok(Insert { table, rows })
+        self.parse_keywords(&[Keyword::Do])?;
+        let action = if self.check_keywords(&[Keyword::Nothing]) {
+            ConflictAction::DoNothing
+        } else {
+            self.parse_keywords(&[Keyword::Update, Keyword::Set])?;
+            let set = self.parse_assignments()?;
+            let filter = if self.check_keywords(&[Keyword::Where]) {
+                Some(self.parse_expr(0)?)
+            } else {
+                None
+            };
+            ConflictAction::DoUpdate { set, filter }
+        };
+
+        Ok(Some(OnConflict::OnConflict { columns, action }))
+    }
+
+    // Parses an optional `RETURNING` projection, returning no items if there isn't one
+    fn parse_returning(&mut self) -> Result<Vec<SelectItem>> {
+        if self.check_keywords(&[Keyword::Returning]) {
+            self.parse_projection()
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    // Parses an expression in an INSERT row, which can also be DEFAULT
+    fn parse_insert_value(&mut self) -> Result<Expr> {
+        let start = self.start();
+        if self.check_keywords(&[Keyword::Default]) {
+            return Ok(Expr::new(ExprKind::Default, self.span_from(start)));
+        }
+
+        self.parse_expr(0)
     }
 
     fn parse_update(&mut self) -> Result<Update> {
@@ -356,6 +637,17 @@ impl Parser {
 
         self.parse_keywords(&[Keyword::Set])?;
 
+        let set = self.parse_assignments()?;
+
+        let filter =
+            if self.check_keywords(&[Keyword::Where]) { Some(self.parse_expr(0)?) } else { None };
+        let returning = self.parse_returning()?;
+
+        Ok(Update { table, set, filter, returning })
+    }
+
+    // Parses the `column = expr, ...` list of an UPDATE or upsert
+    fn parse_assignments(&mut self) -> Result<Vec<Set>> {
         let mut set = Vec::new();
         while {
             let column = self.parse_ident()?;
@@ -367,10 +659,7 @@ impl Parser {
             self.check_tokens(&[Token::Comma])
         } {}
 
-        let filter =
-            if self.check_keywords(&[Keyword::Where]) { Some(self.parse_expr(0)?) } else { None };
-
-        Ok(Update { table, set, filter })
+        Ok(set)
     }
 
     fn parse_delete(&mut self) -> Result<Delete> {
@@ -380,156 +669,613 @@ impl Parser {
 
         let filter =
             if self.check_keywords(&[Keyword::Where]) { Some(self.parse_expr(0)?) } else { None };
+        let returning = self.parse_returning()?;
 
-        Ok(Delete { table, filter })
+        Ok(Delete { table, filter, returning })
     }
 
-    fn parse_create(&mut self) -> Result<Create> {
-        self.parse_keywords(&[Keyword::Create, Keyword::Table])?;
-
-        let TokenWithLocation(token, location) = self.next();
-        let name = match token {
-            Token::Ident(name) => name,
-            _ => Err(Unexpected(&token, &location))?,
+    fn parse_create(&mut self) -> Result<StatementKind> {
+        self.parse_keywords(&[Keyword::Create])?;
+
+        let kind = if self.check_keywords(&[Keyword::Table]) {
+            StatementKind::Create(self.parse_create_table()?)
+        } else if self.check_keywords(&[Keyword::Unique, Keyword::Index]) {
+            StatementKind::CreateIndex(self.parse_create_index(true)?)
+        } else if self.check_keywords(&[Keyword::Index]) {
+            StatementKind::CreateIndex(self.parse_create_index(false)?)
+        } else if self.check_keywords(&[Keyword::Or, Keyword::Replace, Keyword::View]) {
+            StatementKind::CreateView(self.parse_create_view(true)?)
+        } else if self.check_keywords(&[Keyword::View]) {
+            StatementKind::CreateView(self.parse_create_view(false)?)
+        } else {
+            Err(self.unexpected_next())?
         };
 
+        Ok(kind)
+    }
+
+    // Parses the remainder of `CREATE TABLE`
+    fn parse_create_table(&mut self) -> Result<Create> {
+        let if_not_exists = self.check_keywords(&[Keyword::If, Keyword::Not, Keyword::Exists]);
+        let name = self.parse_ident()?;
+
         self.parse_tokens(&[Token::LParen])?;
+        let (mut columns, mut constraints) = (Vec::new(), Vec::new());
+        while {
+            match self.parse_table_constraint()? {
+                Some(constraint) => constraints.push(constraint),
+                None => columns.push(self.parse_column_def()?),
+            }
+            self.check_tokens(&[Token::Comma])
+        } {}
+        self.parse_tokens(&[Token::RParen])?;
+
+        Ok(Create { name, if_not_exists, columns, constraints })
+    }
+
+    // Parses the remainder of `CREATE [UNIQUE] INDEX`
+    fn parse_create_index(&mut self, unique: bool) -> Result<CreateIndex> {
+        let if_not_exists = self.check_keywords(&[Keyword::If, Keyword::Not, Keyword::Exists]);
+        let name = self.parse_identifier()?;
+
+        self.parse_keywords(&[Keyword::On])?;
+        let table = self.parse_ident()?;
+
         let mut columns = Vec::new();
+        self.parse_tokens(&[Token::LParen])?;
         while {
-            columns.push(self.parse_column_def()?);
+            columns.push(self.parse_order_by_expr()?);
             self.check_tokens(&[Token::Comma])
         } {}
         self.parse_tokens(&[Token::RParen])?;
 
-        Ok(Create { name, columns })
+        let filter =
+            if self.check_keywords(&[Keyword::Where]) { Some(self.parse_expr(0)?) } else { None };
+
+        Ok(CreateIndex { name, unique, if_not_exists, table, columns, filter })
     }
 
-    fn parse_column_def(&mut self) -> Result<ColumnDef> {
-        let TokenWithLocation(token, location) = self.next();
-        let name = match token {
-            Token::Ident(name) => name,
-            _ => Err(Unexpected(&token, &location))?,
-        };
+    // Parses the remainder of `CREATE [OR REPLACE] VIEW`
+    fn parse_create_view(&mut self, or_replace: bool) -> Result<CreateView> {
+        let name = self.parse_ident()?;
+        let columns =
+            if self.peek().0 == Token::LParen { self.parse_column_list()? } else { Vec::new() };
 
-        let TokenWithLocation(token, location) = self.next();
-        let ty = match token {
-            Token::Keyword(Keyword::Int) => ColumnType::Int,
-            Token::Keyword(Keyword::Varchar) => {
-                self.parse_tokens(&[Token::LParen])?;
-                let TokenWithLocation(token, location) = self.next();
-                let max = match token {
-                    Token::NumberLiteral(ref max) => {
-                        max.parse().map_err(|_| Unexpected(&token, &location))?
-                    }
-                    _ => Err(Unexpected(&token, &location))?,
-                };
-                self.parse_tokens(&[Token::RParen])?;
-                ColumnType::Varchar(max)
-            }
-            _ => Err(Unexpected(&token, &location))?,
-        };
+        self.parse_keywords(&[Keyword::As])?;
+        let query = self.parse_select()?;
 
-        Ok(ColumnDef { ty, name })
+        Ok(CreateView { name, or_replace, columns, query })
     }
 
-    fn parse_projection(&mut self) -> Result<Vec<SelectItem>> {
-        let mut items = Vec::new();
+    fn parse_alter_table(&mut self) -> Result<AlterTable> {
+        self.parse_keywords(&[Keyword::Alter, Keyword::Table])?;
+
+        let if_exists = self.check_keywords(&[Keyword::If, Keyword::Exists]);
+        let name = self.parse_ident()?;
+
+        let mut operations = Vec::new();
         while {
-            items.push(self.parse_select_item()?);
+            operations.push(self.parse_alter_table_operation()?);
             self.check_tokens(&[Token::Comma])
         } {}
 
-        Ok(items)
+        Ok(AlterTable { name, if_exists, operations })
     }
 
-    fn parse_select_item(&mut self) -> Result<SelectItem> {
-        // Try parse wildcard or qualified wildcard
-        // Parse expr and optional alias
-        let index = self.index;
-        let TokenWithLocation(token, _) = self.next();
-        match token {
-            Token::Asterisk => return Ok(SelectItem::Wildcard),
-            Token::Ident(a) => {
-                // Try to parse a qualified ident, else reset index and parse_expr
-                let mut parts = Vec::with_capacity(2);
-                if self.check_tokens(&[Token::Dot]) {
-                    parts.push(a);
-
-                    let TokenWithLocation(b, location) = self.next();
-                    match b {
-                        Token::Ident(b) => parts.push(b),
-                        Token::Asterisk => return Ok(SelectItem::QualifiedWildcard(parts)),
-                        _ => Err(Unexpected(&b, &location))?,
-                    };
-
-                    if self.check_tokens(&[Token::Dot]) {
-                        let TokenWithLocation(c, location) = self.next();
-                        match c {
-                            Token::Ident(_) => {}
-                            Token::Asterisk => return Ok(SelectItem::QualifiedWildcard(parts)),
-                            _ => Err(Unexpected(&c, &location))?,
-                        };
+    fn parse_alter_table_operation(&mut self) -> Result<AlterTableOperation> {
+        let operation = if self.check_keywords(&[Keyword::Add]) {
+            match self.parse_table_constraint()? {
+                Some(constraint) => AlterTableOperation::AddConstraint(constraint),
+                None => {
+                    self.check_keywords(&[Keyword::Column]);
+                    let if_not_exists =
+                        self.check_keywords(&[Keyword::If, Keyword::Not, Keyword::Exists]);
+                    AlterTableOperation::AddColumn {
+                        if_not_exists,
+                        column: self.parse_column_def()?,
                     }
                 }
             }
-            _ => {}
+        } else if self.check_keywords(&[Keyword::Drop, Keyword::Constraint]) {
+            let if_exists = self.check_keywords(&[Keyword::If, Keyword::Exists]);
+            let name = self.parse_identifier()?;
+            AlterTableOperation::DropConstraint { name, if_exists, cascade: self.parse_cascade() }
+        } else if self.check_keywords(&[Keyword::Drop]) {
+            self.check_keywords(&[Keyword::Column]);
+            let if_exists = self.check_keywords(&[Keyword::If, Keyword::Exists]);
+            let name = self.parse_identifier()?;
+            AlterTableOperation::DropColumn { name, if_exists, cascade: self.parse_cascade() }
+        } else if self.check_keywords(&[Keyword::Rename, Keyword::To]) {
+            AlterTableOperation::RenameTo(self.parse_ident()?)
+        } else if self.check_keywords(&[Keyword::Rename]) {
+            self.check_keywords(&[Keyword::Column]);
+            let from = self.parse_identifier()?;
+            self.parse_keywords(&[Keyword::To])?;
+            AlterTableOperation::RenameColumn { from, to: self.parse_identifier()? }
+        } else if self.check_keywords(&[Keyword::Alter]) {
+            self.check_keywords(&[Keyword::Column]);
+            let name = self.parse_identifier()?;
+            AlterTableOperation::AlterColumn { name, op: self.parse_alter_column_operation()? }
+        } else {
+            Err(self.unexpected_next())?
         };
 
-        self.index = index;
-        let expr = self.parse_expr(0)?;
-        if self.check_keywords(&[Keyword::As]) {
-            let TokenWithLocation(token, location) = self.next();
-            match token {
-                Token::Ident(alias) => return Ok(SelectItem::AliasedExpr { expr, alias }),
-                _ => Err(Unexpected(&token, &location))?,
-            };
+        Ok(operation)
+    }
+
+    fn parse_alter_column_operation(&mut self) -> Result<AlterColumnOperation> {
+        let op = if self.check_keywords(&[Keyword::Set]) {
+            if self.check_keywords(&[Keyword::Not, Keyword::Null]) {
+                AlterColumnOperation::SetNotNull
+            } else if self.check_keywords(&[Keyword::Default]) {
+                AlterColumnOperation::SetDefault(self.parse_expr(0)?)
+            } else {
+                self.parse_keywords(&[Keyword::Data, Keyword::Type])?;
+                AlterColumnOperation::SetType(self.parse_column_type()?)
+            }
+        } else if self.check_keywords(&[Keyword::Drop]) {
+            if self.check_keywords(&[Keyword::Default]) {
+                AlterColumnOperation::DropDefault
+            } else {
+                self.parse_keywords(&[Keyword::Not, Keyword::Null])?;
+                AlterColumnOperation::DropNotNull
+            }
+        } else if self.check_keywords(&[Keyword::Type]) {
+            AlterColumnOperation::SetType(self.parse_column_type()?)
+        } else {
+            Err(self.unexpected_next())?
         };
 
-        Ok(SelectItem::Expr(expr))
+        Ok(op)
     }
 
-    fn parse_expr(&mut self, prec: u8) -> Result<Expr> {
-        let mut expr = self.parse_prefix()?;
+    fn parse_drop(&mut self) -> Result<Drop> {
+        self.parse_keywords(&[Keyword::Drop])?;
+
+        let object = if self.check_keywords(&[Keyword::Table]) {
+            ObjectType::Table
+        } else if self.check_keywords(&[Keyword::Index]) {
+            ObjectType::Index
+        } else if self.check_keywords(&[Keyword::View]) {
+            ObjectType::View
+        } else {
+            Err(self.unexpected_next())?
+        };
+
+        let if_exists = self.check_keywords(&[Keyword::If, Keyword::Exists]);
+        let mut names = Vec::new();
+        while {
+            names.push(self.parse_ident()?);
+            self.check_tokens(&[Token::Comma])
+        } {}
+
+        Ok(Drop { object, if_exists, names, cascade: self.parse_cascade() })
+    }
+
+    fn parse_truncate(&mut self) -> Result<Truncate> {
+        self.parse_keywords(&[Keyword::Truncate])?;
+        self.check_keywords(&[Keyword::Table]);
+
+        let mut tables = Vec::new();
+        while {
+            tables.push(self.parse_ident()?);
+            self.check_tokens(&[Token::Comma])
+        } {}
+
+        Ok(Truncate { tables, cascade: self.parse_cascade() })
+    }
+
+    // Parses an optional `CASCADE` or `RESTRICT`, returning true for the former
+    fn parse_cascade(&mut self) -> bool {
+        if self.check_keywords(&[Keyword::Cascade]) {
+            return true;
+        }
+        self.check_keywords(&[Keyword::Restrict]);
+
+        false
+    }
+
+    fn parse_column_def(&mut self) -> Result<ColumnDef> {
+        let name = self.parse_identifier()?;
+
+        let ty = self.parse_column_type()?;
+
+        let mut constraints = Vec::new();
+        while let Some(constraint) = self.parse_column_constraint()? {
+            constraints.push(constraint);
+        }
+
+        Ok(ColumnDef { ty, name, constraints })
+    }
+
+    fn parse_column_type(&mut self) -> Result<ColumnType> {
+        let mut ty = if self.check_keywords(&[Keyword::Smallint]) {
+            ColumnType::SmallInt
+        } else if self.check_keywords(&[Keyword::Int]) || self.check_keywords(&[Keyword::Integer]) {
+            ColumnType::Int
+        } else if self.check_keywords(&[Keyword::Bigint]) {
+            ColumnType::BigInt
+        } else if self.check_keywords(&[Keyword::Boolean]) || self.check_keywords(&[Keyword::Bool])
+        {
+            ColumnType::Boolean
+        } else if self.check_keywords(&[Keyword::Real]) {
+            ColumnType::Real
+        } else if self.check_keywords(&[Keyword::Double]) {
+            self.check_keywords(&[Keyword::Precision]);
+            ColumnType::Double
+        } else if self.check_keywords(&[Keyword::Decimal])
+            || self.check_keywords(&[Keyword::Numeric])
+        {
+            let (mut precision, mut scale) = (None, None);
+            if self.check_tokens(&[Token::LParen]) {
+                precision = Some(self.parse_type_length()?);
+                if self.check_tokens(&[Token::Comma]) {
+                    scale = Some(self.parse_type_length()?);
+                }
+                self.parse_tokens(&[Token::RParen])?;
+            }
+            ColumnType::Decimal { precision, scale }
+        } else if self.check_keywords(&[Keyword::Char]) {
+            let mut len = None;
+            if self.check_tokens(&[Token::LParen]) {
+                len = Some(self.parse_type_length()?);
+                self.parse_tokens(&[Token::RParen])?;
+            }
+            ColumnType::Char(len)
+        } else if self.check_keywords(&[Keyword::Varchar]) {
+            self.parse_tokens(&[Token::LParen])?;
+            let max = self.parse_type_length()?;
+            self.parse_tokens(&[Token::RParen])?;
+            ColumnType::Varchar(max)
+        } else if self.check_keywords(&[Keyword::Text]) {
+            ColumnType::Text
+        } else if self.check_keywords(&[Keyword::Date]) {
+            ColumnType::Date
+        } else if self.check_keywords(&[Keyword::Time]) {
+            ColumnType::Time { with_time_zone: self.parse_time_zone() }
+        } else if self.check_keywords(&[Keyword::Timestamp]) {
+            ColumnType::Timestamp { with_time_zone: self.parse_time_zone() }
+        } else if self.check_keywords(&[Keyword::Blob]) {
+            ColumnType::Blob
+        } else if self.check_keywords(&[Keyword::Bytea]) {
+            ColumnType::Bytea
+        } else if self.check_keywords(&[Keyword::Json]) {
+            ColumnType::Json
+        } else {
+            let name = self.parse_identifier()?;
+            let mut args = Vec::new();
+            if self.check_tokens(&[Token::LParen]) {
+                while {
+                    args.push(self.parse_type_arg()?);
+                    self.check_tokens(&[Token::Comma])
+                } {}
+                self.parse_tokens(&[Token::RParen])?;
+            }
+            ColumnType::Custom(name, args)
+        };
+
+        while self.check_tokens(&[Token::LBracket, Token::RBracket])
+            || self.check_keywords(&[Keyword::Array])
+        {
+            ty = ColumnType::Array(Box::new(ty));
+        }
+
+        Ok(ty)
+    }
+
+    // Parses a length, precision or scale in a column type
+    fn parse_type_length(&mut self) -> Result<u32> {
+        let TokenWithSpan(token, span) = self.peek();
+        let len = match token {
+            Token::NumberLiteral(n) => match n.replace('_', "").parse() {
+                Ok(len) => len,
+                Err(_) if n.bytes().all(|b| b.is_ascii_digit() || b == b'_') => {
+                    Err(ParserError::NumberOutOfRange { literal: n.to_string(), span: *span })?
+                }
+                Err(_) => Err(self.unexpected_next())?,
+            },
+            _ => Err(self.unexpected_next())?,
+        };
+        self.next();
+
+        Ok(len)
+    }
+
+    // Parses an argument of a custom column type as SQL text
+    fn parse_type_arg(&mut self) -> Result<String> {
+        let arg = match &self.peek().0 {
+            Token::NumberLiteral(n) => n.to_string(),
+            Token::StringLiteral(s) => Value::String(s.to_string()).to_string(),
+            _ => return Ok(self.parse_identifier()?.to_string()),
+        };
+        self.next();
+
+        Ok(arg)
+    }
+
+    // Parses an optional `WITH TIME ZONE` or `WITHOUT TIME ZONE`, returning true for the former
+    fn parse_time_zone(&mut self) -> bool {
+        if self.check_keywords(&[Keyword::With, Keyword::Time, Keyword::Zone]) {
+            return true;
+        }
+        self.check_keywords(&[Keyword::Without, Keyword::Time, Keyword::Zone]);
+
+        false
+    }
+
+    // Parses a constraint following a column's type, returns None if there isn't one
+    fn parse_column_constraint(&mut self) -> Result<Option<ColumnConstraint>> {
+        let name = self.parse_constraint_name()?;
+
+        let kind = if self.check_keywords(&[Keyword::Not, Keyword::Null]) {
+            ColumnConstraintKind::NotNull
+        } else if self.check_keywords(&[Keyword::Null]) {
+            ColumnConstraintKind::Null
+        } else if self.check_keywords(&[Keyword::Default]) {
+            ColumnConstraintKind::Default(self.parse_expr(0)?)
+        } else if self.check_keywords(&[Keyword::Primary, Keyword::Key]) {
+            ColumnConstraintKind::PrimaryKey
+        } else if self.check_keywords(&[Keyword::Unique]) {
+            ColumnConstraintKind::Unique
+        } else if self.check_keywords(&[Keyword::Check]) {
+            ColumnConstraintKind::Check(self.parse_check()?)
+        } else if self.check_keywords(&[Keyword::References]) {
+            ColumnConstraintKind::References(self.parse_references()?)
+        } else if name.is_some() {
+            Err(self.unexpected_next())?
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(ColumnConstraint { name, kind }))
+    }
+
+    // Parses a constraint in the column list, returns None if the next item is a column
+    fn parse_table_constraint(&mut self) -> Result<Option<TableConstraint>> {
+        let name = self.parse_constraint_name()?;
+
+        let kind = if self.check_keywords(&[Keyword::Primary, Keyword::Key]) {
+            TableConstraintKind::PrimaryKey(self.parse_column_list()?)
+        } else if self.check_keywords(&[Keyword::Unique]) {
+            TableConstraintKind::Unique(self.parse_column_list()?)
+        } else if self.check_keywords(&[Keyword::Foreign, Keyword::Key]) {
+            let columns = self.parse_column_list()?;
+            self.parse_keywords(&[Keyword::References])?;
+            TableConstraintKind::ForeignKey { columns, references: self.parse_references()? }
+        } else if self.check_keywords(&[Keyword::Check]) {
+            TableConstraintKind::Check(self.parse_check()?)
+        } else if name.is_some() {
+            Err(self.unexpected_next())?
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(TableConstraint { name, kind }))
+    }
+
+    fn parse_constraint_name(&mut self) -> Result<Option<Name>> {
+        if self.check_keywords(&[Keyword::Constraint]) {
+            Ok(Some(self.parse_identifier()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Parses the parenthesised expression of a CHECK constraint
+    fn parse_check(&mut self) -> Result<Expr> {
+        self.parse_tokens(&[Token::LParen])?;
+        let expr = self.parse_expr(0)?;
+        self.parse_tokens(&[Token::RParen])?;
+
+        Ok(expr)
+    }
+
+    // Parses the remainder of `REFERENCES table [(columns)] [ON DELETE action] [ON UPDATE action]`
+    fn parse_references(&mut self) -> Result<References> {
+        let table = self.parse_ident()?;
+        let columns =
+            if self.peek().0 == Token::LParen { self.parse_column_list()? } else { Vec::new() };
+
+        let (mut on_delete, mut on_update) = (None, None);
         loop {
-            let next_prec = self.next_prec()?;
-            if prec >= next_prec {
+            if on_delete.is_none() && self.check_keywords(&[Keyword::On, Keyword::Delete]) {
+                on_delete = Some(self.parse_referential_action()?);
+            } else if on_update.is_none() && self.check_keywords(&[Keyword::On, Keyword::Update]) {
+                on_update = Some(self.parse_referential_action()?);
+            } else {
                 break;
             }
-            expr = self.parse_infix(expr, next_prec)?;
         }
 
-        Ok(expr)
+        Ok(References { table, columns, on_delete, on_update })
+    }
+
+    fn parse_referential_action(&mut self) -> Result<ReferentialAction> {
+        let action = if self.check_keywords(&[Keyword::No, Keyword::Action]) {
+            ReferentialAction::NoAction
+        } else if self.check_keywords(&[Keyword::Restrict]) {
+            ReferentialAction::Restrict
+        } else if self.check_keywords(&[Keyword::Cascade]) {
+            ReferentialAction::Cascade
+        } else if self.check_keywords(&[Keyword::Set, Keyword::Null]) {
+            ReferentialAction::SetNull
+        } else if self.check_keywords(&[Keyword::Set, Keyword::Default]) {
+            ReferentialAction::SetDefault
+        } else {
+            Err(self.unexpected_next())?
+        };
+
+        Ok(action)
+    }
+
+    // Parses a parenthesised list of column names
+    fn parse_column_list(&mut self) -> Result<Vec<Name>> {
+        let mut columns = Vec::new();
+
+        self.parse_tokens(&[Token::LParen])?;
+        while {
+            columns.push(self.parse_identifier()?);
+            self.check_tokens(&[Token::Comma])
+        } {}
+        self.parse_tokens(&[Token::RParen])?;
+
+        Ok(columns)
+    }
+
+    fn parse_projection(&mut self) -> Result<Vec<SelectItem>> {
+        let mut items = Vec::new();
+        while {
+            items.push(self.parse_select_item()?);
+            self.check_tokens(&[Token::Comma])
+        } {}
+
+        Ok(items)
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem> {
+        // Try parse wildcard or qualified wildcard, else reset index and parse expr and optional
+        // alias
+        let (index, start) = (self.index, self.start());
+        let kind = match self.parse_wildcard_item()? {
+            Some(kind) => kind,
+            None => {
+                self.index = index;
+                let expr = self.parse_expr(0)?;
+                if self.check_keywords(&[Keyword::As]) {
+                    let alias = self.parse_identifier()?;
+                    SelectItemKind::AliasedExpr { expr, alias }
+                } else {
+                    SelectItemKind::Expr(expr)
+                }
+            }
+        };
+
+        Ok(SelectItem::new(kind, self.span_from(start)))
+    }
+
+    // Parses `*`, `t1.*` or `s1.t1.*`, returns None if the item isn't a wildcard
+    fn parse_wildcard_item(&mut self) -> Result<Option<SelectItemKind>> {
+        let a = match self.next().clone() {
+            TokenWithSpan(Token::Asterisk, _) => return Ok(Some(SelectItemKind::Wildcard)),
+            TokenWithSpan(Token::Ident(a), span) => self.name(&a, span),
+            _ => return Ok(None),
+        };
+        if !self.check_tokens(&[Token::Dot]) {
+            return Ok(None);
+        }
+
+        let mut parts = vec![a];
+        match self.peek() {
+            TokenWithSpan(Token::Ident(b), span) => parts.push(self.name(b, *span)),
+            TokenWithSpan(Token::Asterisk, _) => {
+                self.next();
+                return Ok(Some(SelectItemKind::QualifiedWildcard(parts)));
+            }
+            _ => Err(self.unexpected_next())?,
+        };
+        self.next();
+
+        if self.check_tokens(&[Token::Dot]) {
+            match self.peek().0 {
+                Token::Ident(_) => {}
+                Token::Asterisk => {
+                    self.next();
+                    return Ok(Some(SelectItemKind::QualifiedWildcard(parts)));
+                }
+                _ => Err(self.unexpected_next())?,
+            };
+        }
+
+        Ok(None)
+    }
+
+    fn parse_expr(&mut self, prec: u8) -> Result<Expr> {
+        self.recurse(|parser| {
+            let mut expr = parser.parse_prefix()?;
+            loop {
+                let next_prec = parser.next_prec();
+                if prec >= next_prec {
+                    break;
+                }
+                // Each operator nests the expression so far a level deeper, so a long chain like
+                // `a + b + c` counts against the limit as much as nesting does
+                parser.descend()?;
+                expr = parser.parse_infix(expr, next_prec)?;
+            }
+
+            Ok(expr)
+        })
     }
 
     fn parse_prefix(&mut self) -> Result<Expr> {
-        let TokenWithLocation(token, location) = self.peek();
-        let expr = match token {
+        let TokenWithSpan(token, span) = self.peek();
+        let start = span.start;
+        let kind = match token {
             Token::Keyword(Keyword::False)
             | Token::Keyword(Keyword::True)
             | Token::Keyword(Keyword::Null)
             | Token::StringLiteral(_)
-            | Token::NumberLiteral(_) => Expr::Value(self.parse_value()?),
+            | Token::HexStringLiteral(_)
+            | Token::NumberLiteral(_) => ExprKind::Value(self.parse_value()?),
+
+            // Typed numbers fold in their sign so e.g. i64::MIN is in range
+            Token::Minus if self.number_mode != NumberMode::Text => match &self.peek_n(1).0 {
+                Token::NumberLiteral(n) => {
+                    let (n, end) = (n.clone(), self.peek_n(1).1);
+                    let value = self.number(&n, true, Span { start, end: end.end })?;
+                    self.next();
+                    self.next();
+                    ExprKind::Value(value)
+                }
+                _ => {
+                    self.next();
+                    let expr = Box::new(self.parse_expr(50)?);
+                    ExprKind::UnaryOp { op: UnaryOp::Minus, expr }
+                }
+            },
 
-            Token::Keyword(Keyword::Select) => Expr::SubQuery(self.parse_query().map(Box::new)?),
+            Token::Keyword(Keyword::Select) => {
+                ExprKind::SubQuery(self.parse_query().map(Box::new)?)
+            }
 
-            Token::Ident(_) => Expr::Ident(self.parse_ident()?),
+            Token::Ident(_) | Token::Keyword(_) if self.is_identifier(token) => {
+                let ident = self.parse_ident()?;
+                if self.check_tokens(&[Token::LParen]) {
+                    self.parse_function(ident)?
+                } else {
+                    ExprKind::Ident(ident)
+                }
+            }
 
             Token::LParen => {
                 self.next();
                 let expr = self.parse_expr(0)?;
                 self.parse_tokens(&[Token::RParen])?;
-                expr
+                return Ok(expr);
             }
 
-            _ => Err(Unexpected(&token, &location))?,
+            Token::Plus | Token::Minus | Token::Keyword(Keyword::Not) => {
+                let (op, prec) = match token {
+                    Token::Plus => (UnaryOp::Plus, 50),
+                    Token::Minus => (UnaryOp::Minus, 50),
+                    _ => (UnaryOp::Not, 15),
+                };
+                self.next();
+                ExprKind::UnaryOp { op, expr: Box::new(self.parse_expr(prec)?) }
+            }
+
+            _ => {
+                self.expect(Expected::Expression);
+                Err(self.unexpected_next())?
+            }
         };
 
-        Ok(expr)
+        Ok(Expr::new(kind, self.span_from(start)))
     }
 
     fn parse_infix(&mut self, expr: Expr, prec: u8) -> Result<Expr> {
-        let TokenWithLocation(token, location) = self.next();
-        let op = match token {
+        let start = expr.span.start;
+        let op = match &self.peek().0 {
             Token::Keyword(kw) => match kw {
                 Keyword::And => Some(Op::And),
                 Keyword::Or => Some(Op::Or),
@@ -541,90 +1287,164 @@ impl Parser {
             Token::Le => Some(Op::Le),
             Token::Gt => Some(Op::Gt),
             Token::Ge => Some(Op::Ge),
+            Token::Plus => Some(Op::Plus),
+            Token::Minus => Some(Op::Minus),
+            Token::Asterisk => Some(Op::Multiply),
+            Token::Slash => Some(Op::Divide),
+            Token::Percent => Some(Op::Modulo),
+            Token::Concat => Some(Op::Concat),
             _ => None,
         };
 
         if let Some(op) = op {
-            return Ok(Expr::BinaryOp {
-                left: Box::new(expr),
-                op,
-                right: Box::new(self.parse_expr(prec)?),
-            });
+            if !self.dialect.supports_operator(&op) {
+                Err(self.unexpected_next())?
+            }
+            self.next();
+
+            let right = Box::new(self.parse_expr(prec)?);
+            let kind = ExprKind::BinaryOp { left: Box::new(expr), op, right };
+            return Ok(Expr::new(kind, self.span_from(start)));
         }
 
-        let expr = match token {
+        let kind = match self.peek().0 {
             Token::Keyword(kw) => match kw {
                 Keyword::Is => {
+                    self.next();
                     let negated = self.check_keywords(&[Keyword::Not]);
-                    let TokenWithLocation(token, location) = self.next();
-                    match token {
-                        Token::Keyword(Keyword::Null) if negated => Expr::IsNotNull(Box::new(expr)),
-                        Token::Keyword(Keyword::Null) => Expr::IsNull(Box::new(expr)),
-                        _ => Err(Unexpected(&token, &location))?,
+                    self.parse_keywords(&[Keyword::Null])?;
+                    if negated {
+                        ExprKind::IsNotNull(Box::new(expr))
+                    } else {
+                        ExprKind::IsNull(Box::new(expr))
                     }
                 }
                 Keyword::Not | Keyword::Between | Keyword::In => {
-                    self.index -= 1;
                     let negated = self.check_keywords(&[Keyword::Not]);
                     if self.check_keywords(&[Keyword::Between]) {
                         self.parse_between(expr, negated)?
                     } else if self.check_keywords(&[Keyword::In]) {
                         self.parse_in(expr, negated)?
                     } else {
-                        // Should be the next token?
-                        Err(Unexpected(&token, &location))?
+                        Err(self.unexpected_next())?
                     }
                 }
-                _ => Err(Unexpected(&token, &location))?,
+                _ => Err(self.unexpected_next())?,
             },
-            _ => Err(Unexpected(&token, &location))?,
+            _ => Err(self.unexpected_next())?,
         };
 
-        Ok(expr)
+        Ok(Expr::new(kind, self.span_from(start)))
     }
 
-    fn next_prec(&self) -> Result<u8> {
-        let TokenWithLocation(token, _) = self.peek();
-        let prec = match token {
+    fn next_prec(&self) -> u8 {
+        match self.peek().0 {
+            Token::Asterisk | Token::Slash | Token::Percent => 40,
+            Token::Plus | Token::Minus => 30,
+            Token::Concat => 25,
             Token::Eq | Token::Neq | Token::Lt | Token::Le | Token::Gt | Token::Ge => 20,
             Token::Keyword(Keyword::And) => 10,
             Token::Keyword(Keyword::Or) => 5,
 
-            Token::Keyword(Keyword::Not) => {
-                let TokenWithLocation(token, location) = self.peek_n(1);
-                match token {
-                    Token::Keyword(Keyword::Between) => 20,
-                    Token::Keyword(Keyword::In) => 20,
-                    _ => Err(Unexpected(&token, &location))?,
-                }
-            }
+            // Otherwise the NOT belongs to whatever follows, e.g. `DEFAULT 0 NOT NULL`
+            Token::Keyword(Keyword::Not) => match self.peek_n(1).0 {
+                Token::Keyword(Keyword::Between) => 20,
+                Token::Keyword(Keyword::In) => 20,
+                _ => 0,
+            },
             Token::Keyword(Keyword::Is) => 17,
             Token::Keyword(Keyword::Between) => 20,
             Token::Keyword(Keyword::In) => 20,
             _ => 0,
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        let TokenWithSpan(token, span) = self.peek();
+        let value = match token {
+            Token::Keyword(Keyword::False) => Value::Bool(false),
+            Token::Keyword(Keyword::True) => Value::Bool(true),
+            Token::Keyword(Keyword::Null) => Value::Null,
+            Token::StringLiteral(s) => Value::String(s.to_string()),
+            Token::HexStringLiteral(s) => Value::HexString(s.to_string()),
+            Token::NumberLiteral(n) => self.number(n, false, *span)?,
+            _ => Err(self.unexpected_next())?,
         };
+        self.next();
 
-        Ok(prec)
+        Ok(value)
     }
 
-    fn parse_value(&mut self) -> Result<Value> {
-        let TokenWithLocation(token, location) = self.next();
-        match token {
-            Token::Keyword(Keyword::False) => Ok(Value::Bool(false)),
-            Token::Keyword(Keyword::True) => Ok(Value::Bool(true)),
-            Token::Keyword(Keyword::Null) => Ok(Value::Null),
-            Token::StringLiteral(s) => Ok(Value::String(s)),
-            Token::NumberLiteral(n) => Ok(Value::Number(n)),
-            _ => Err(Unexpected(&token, &location))?,
+    // Converts a number literal to a value according to the number mode
+    fn number(&self, literal: &str, negative: bool, span: Span) -> Result<Value> {
+        if self.number_mode == NumberMode::Text {
+            return Ok(Value::Number(literal.to_owned()));
+        }
+
+        typed_number(literal, negative, self.number_mode).ok_or_else(|| {
+            let sign = if negative { "-" } else { "" };
+            ParserError::NumberOutOfRange { literal: format!("{sign}{literal}"), span }
+        })
+    }
+
+    // Parses the remainder of a function call after the opening paren
+    fn parse_function(&mut self, name: Ident) -> Result<ExprKind> {
+        let distinct = self.check_keywords(&[Keyword::Distinct]);
+
+        // DISTINCT needs an argument to apply to
+        let mut args = Vec::new();
+        if distinct || !self.check_tokens(&[Token::RParen]) {
+            while {
+                args.push(self.parse_function_arg()?);
+                self.check_tokens(&[Token::Comma])
+            } {}
+            self.parse_tokens(&[Token::RParen])?;
+        }
+
+        let filter = if self.check_keywords(&[Keyword::Filter]) {
+            self.parse_tokens(&[Token::LParen])?;
+            self.parse_keywords(&[Keyword::Where])?;
+            let filter = self.parse_expr(0)?;
+            self.parse_tokens(&[Token::RParen])?;
+            Some(Box::new(filter))
+        } else {
+            None
+        };
+
+        Ok(ExprKind::Function { name, args, distinct, filter })
+    }
+
+    fn parse_function_arg(&mut self) -> Result<Expr> {
+        // Try parse wildcard or qualified wildcard, else reset index and parse_expr
+        let (index, start) = (self.index, self.start());
+        let mut parts = Vec::new();
+        loop {
+            let kind = match self.next().clone() {
+                TokenWithSpan(Token::Asterisk, _) if parts.is_empty() => ExprKind::Wildcard,
+                TokenWithSpan(Token::Asterisk, _) => ExprKind::QualifiedWildcard(parts),
+                TokenWithSpan(Token::Ident(a), span) => {
+                    parts.push(self.name(&a, span));
+                    if self.check_tokens(&[Token::Dot]) {
+                        continue;
+                    }
+                    break;
+                }
+                _ => break,
+            };
+
+            return Ok(Expr::new(kind, self.span_from(start)));
         }
+
+        self.index = index;
+        self.parse_expr(0)
     }
 
-    fn parse_between(&mut self, expr: Expr, negated: bool) -> Result<Expr> {
+    fn parse_between(&mut self, expr: Expr, negated: bool) -> Result<ExprKind> {
         let low = self.parse_expr(20)?;
         self.parse_keywords(&[Keyword::And])?;
         let high = self.parse_expr(20)?;
 
-        Ok(Expr::Between {
+        Ok(ExprKind::Between {
             expr: Box::new(expr),
             negated,
             low: Box::new(low),
@@ -632,7 +1452,7 @@ impl Parser {
         })
     }
 
-    fn parse_in(&mut self, expr: Expr, negated: bool) -> Result<Expr> {
+    fn parse_in(&mut self, expr: Expr, negated: bool) -> Result<ExprKind> {
         let mut list = Vec::new();
 
         self.parse_tokens(&[Token::LParen])?;
@@ -642,29 +1462,19 @@ impl Parser {
         } {}
         self.parse_tokens(&[Token::RParen])?;
 
-        Ok(Expr::InList { expr: Box::new(expr), list, negated })
+        Ok(ExprKind::InList { expr: Box::new(expr), list, negated })
     }
 
     fn parse_ident(&mut self) -> Result<Ident> {
-        let TokenWithLocation(token, location) = self.next();
-        let Token::Ident(a) = token else { Err(Unexpected(&token, &location))? };
+        let a = self.parse_identifier()?;
 
         let mut parts = Vec::with_capacity(3);
         let ident = if self.check_tokens(&[Token::Dot]) {
             parts.push(a);
-
-            let TokenWithLocation(b, location) = self.next();
-            match b {
-                Token::Ident(b) => parts.push(b),
-                _ => Err(Unexpected(&b, &location))?,
-            };
+            parts.push(self.parse_identifier()?);
 
             if self.check_tokens(&[Token::Dot]) {
-                let TokenWithLocation(c, location) = self.next();
-                match c {
-                    Token::Ident(c) => parts.push(c),
-                    _ => Err(Unexpected(&c, &location))?,
-                };
+                parts.push(self.parse_identifier()?);
             }
 
             Ident::Compound(parts)
@@ -675,17 +1485,55 @@ impl Parser {
         Ok(ident)
     }
 
+    // Parses a single identifier. Non-reserved keywords are accepted as unquoted identifiers written
+    // as they are in the source, see `dialect::is_reserved`
+    fn parse_identifier(&mut self) -> Result<Name> {
+        let TokenWithSpan(token, span) = self.peek();
+        let ident = match token {
+            Token::Ident(ident) => self.name(ident, *span),
+            Token::Keyword(_) if self.is_identifier(token) => {
+                let range = span.start.offset - self.src_offset..span.end.offset - self.src_offset;
+                Name::new(&self.src[range])
+            }
+            _ => {
+                self.expect(Expected::Identifier);
+                Err(self.unexpected_next())?
+            }
+        };
+        self.next();
+
+        Ok(ident)
+    }
+
+    // The name of an identifier token read from `span`, which is quoted if it starts with a quote
+    fn name(&self, ident: &str, span: Span) -> Name {
+        let start = span.start.offset - self.src_offset;
+        match self.src[start..].starts_with(['"', '`']) {
+            true => Name::quoted(ident),
+            false => Name::new(ident),
+        }
+    }
+
+    fn is_identifier(&self, token: &Token) -> bool {
+        match token {
+            Token::Ident(_) => true,
+            Token::Keyword(kw) => !dialect::is_reserved(self.dialect, *kw),
+            _ => false,
+        }
+    }
+
     // Will advance and return true if tokens match, otherwise walk back and return false
     fn check_tokens(&mut self, tokens: &[Token]) -> bool {
         let index = self.index;
 
         for want in tokens {
             match self.peek() {
-                TokenWithLocation(ref have, ..) if want == have => {
+                TokenWithSpan(have, ..) if want == have => {
                     self.next();
                     continue;
                 }
                 _ => {
+                    self.expect(Expected::Token(want.clone().into_owned()));
                     self.index = index;
                     return false;
                 }
@@ -700,11 +1548,12 @@ impl Parser {
 
         for want in keywords {
             match self.peek() {
-                TokenWithLocation(Token::Keyword(ref have), ..) if want == have => {
+                TokenWithSpan(Token::Keyword(have), ..) if want == have => {
                     self.next();
                     continue;
                 }
                 _ => {
+                    self.expect(Expected::Token(Token::Keyword(*want)));
                     self.index = index;
                     return false;
                 }
@@ -716,93 +1565,838 @@ impl Parser {
 
     fn parse_keywords(&mut self, keywords: &[Keyword]) -> Result<()> {
         for want in keywords {
-            let TokenWithLocation(token, location) = self.next();
-            match token {
-                Token::Keyword(ref have) if want == have => continue,
-                _ => Err(Unexpected(&token, &location))?,
+            match self.peek() {
+                TokenWithSpan(Token::Keyword(have), _) if want == have => self.next(),
+                _ => {
+                    self.expect(Expected::Token(Token::Keyword(*want)));
+                    Err(self.unexpected_next())?
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    fn parse_tokens(&mut self, tokens: &[Token]) -> Result<()> {
+        for want in tokens {
+            if want == &self.peek().0 {
+                self.next();
+                continue;
+            }
+
+            self.expect(Expected::Token(want.clone().into_owned()));
+            Err(self.unexpected_next())?;
+        }
+
+        Ok(())
+    }
+
+    fn start(&self) -> Location {
+        self.peek().1.start
+    }
+
+    // The span from `start` to the end of the last consumed token
+    fn span_from(&self, start: Location) -> Span {
+        let end = self.get(self.index.saturating_sub(1)).1.end;
+        Span { start, end }
+    }
+
+    // Record that `expected` would have been accepted at the current index
+    fn expect(&mut self, expected: Expected) {
+        if self.index > self.expected_index {
+            self.expected.clear();
+            self.expected_index = self.index;
+        }
+
+        if self.index == self.expected_index && !self.expected.contains(&expected) {
+            self.expected.push(expected);
+        }
+    }
+
+    // An unexpected error for the next token
+    fn unexpected_next(&self) -> ParserError {
+        let TokenWithSpan(token, span) = self.peek();
+        self.unexpected(token, *span)
+    }
+
+    fn unexpected(&self, token: &Token, span: Span) -> ParserError {
+        let expected = match self.get(self.expected_index) {
+            TokenWithSpan(_, s) if *s == span => self.expected.clone(),
+            _ => vec![],
+        };
+
+        ParserError::Unexpected { token: token.clone().into_owned(), span, expected }
+    }
+
+    // Runs `f` a level deeper, restoring the depth afterwards as `f` can go deeper itself
+    fn recurse<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let depth = self.depth;
+        self.descend()?;
+        let res = f(self);
+        self.depth = depth;
+
+        res
+    }
+
+    fn descend(&mut self) -> Result<()> {
+        if self.depth >= self.recursion_limit {
+            Err(ParserError::RecursionLimit { span: self.peek().1 })?
+        }
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    fn next(&mut self) -> &TokenWithSpan<'a> {
+        self.index += 1;
+        self.get(self.index - 1)
+    }
+
+    fn peek(&self) -> &TokenWithSpan<'a> {
+        self.peek_n(0)
+    }
+
+    fn peek_n(&self, n: usize) -> &TokenWithSpan<'a> {
+        self.get(self.index + n)
+    }
+
+    fn get(&self, i: usize) -> &TokenWithSpan<'a> {
+        // The tokeniser always ends with Eof, which is returned for any index past the end
+        &self.tokens[i.min(self.tokens.len() - 1)]
+    }
+}
+
+// Parses a number literal as an Int, or as a Float or Decimal if it has a fraction or exponent.
+// Returns None if it's out of range
+fn typed_number(literal: &str, negative: bool, mode: NumberMode) -> Option<Value> {
+    let literal = literal.replace('_', "");
+    let sign = if negative { -1 } else { 1 };
+
+    let radix = match literal.get(..2) {
+        Some("0x" | "0X") => Some(16),
+        Some("0b" | "0B") => Some(2),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        let n = i128::from_str_radix(&literal[2..], radix).ok()?;
+        return i64::try_from(sign * n).ok().map(Value::Int);
+    }
+
+    if literal.bytes().all(|b| b.is_ascii_digit()) {
+        let n: i128 = literal.parse().ok()?;
+        return i64::try_from(sign * n).ok().map(Value::Int);
+    }
+
+    match mode {
+        NumberMode::Float => {
+            let n: f64 = literal.parse().ok()?;
+            n.is_finite().then_some(Value::Float(sign as f64 * n))
+        }
+        _ => {
+            let (number, exponent) = match literal.find(['e', 'E']) {
+                Some(i) => (&literal[..i], literal[i + 1..].parse::<i64>().ok()?),
+                None => (&literal[..], 0),
+            };
+            let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+
+            // Trailing zeros are dropped first, so zero or a long run of zeros with a large
+            // exponent is still in range
+            let digits = format!("{int}{frac}");
+            let significant = digits.trim_end_matches('0');
+            if significant.is_empty() {
+                return Some(Value::Decimal(Decimal::new(0, 0)));
+            }
+            let zeros = (digits.len() - significant.len()) as i64;
+
+            let mut mantissa = sign * significant.parse::<i128>().ok()?;
+            let mut scale = (frac.len() as i64).checked_sub(zeros)?.checked_sub(exponent)?;
+            if scale < 0 {
+                let exp = u32::try_from(scale.checked_neg()?).ok()?;
+                mantissa = mantissa.checked_mul(10i128.checked_pow(exp)?)?;
+                scale = 0;
+            }
+
+            let scale = u32::try_from(scale).ok().filter(|&s| s <= Decimal::MAX_SCALE)?;
+            Some(Value::Decimal(Decimal::new(mantissa, scale)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ast::{FromTable, Join, JoinConstraint, JoinType},
+        dialect::{Dialect, GenericDialect, MySqlDialect, PostgresDialect, SqliteDialect},
+        Keyword, Location, QuoteStyle, Span, Token,
+    };
+
+    use super::{
+        AlterColumnOperation, AlterTable, AlterTableOperation, ColumnConstraint,
+        ColumnConstraintKind, ColumnDef, ColumnType, ConflictAction, Create, CreateIndex, Decimal,
+        Delete, Drop, Expected, Expr, ExprKind, FromTableKind, Ident, Insert, InsertSource, Name,
+        NumberMode, ObjectType, OnConflict, Op, OrderByExpr, Parser, ParserError, Query,
+        References, ReferentialAction, Select, SelectItem, SelectItemKind, Set, Statement,
+        StatementKind, TableConstraint, TableConstraintKind, Truncate, UnaryOp, Update, Value,
+    };
+
+    #[test]
+    fn test_create_statement() {
+        let input = "
+            CREATE TABLE t1 (
+                c1 INT,
+                c2 VARCHAR(1024)
+            )";
+
+        let want: Vec<Statement> = vec![StatementKind::Create(Create {
+            name: Ident::Single("t1".into()),
+            if_not_exists: false,
+            columns: vec![
+                ColumnDef { ty: ColumnType::Int, name: "c1".into(), constraints: vec![] },
+                ColumnDef { ty: ColumnType::Varchar(1024), name: "c2".into(), constraints: vec![] },
+            ],
+            constraints: vec![],
+        })
+        .into()];
+
+        let have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        assert_eq!(want, have)
+    }
+
+    #[test]
+    fn test_create_index_and_view() {
+        let input = "
+            CREATE UNIQUE INDEX IF NOT EXISTS i1 ON s1.t1 (c1, c2 DESC) WHERE c1 > 0;
+            CREATE INDEX i2 ON t1 (lower(c3) NULLS FIRST);
+            CREATE OR REPLACE VIEW v1 (a) AS SELECT c1 FROM t1 LIMIT 1;
+            CREATE VIEW s1.v2 AS SELECT * FROM t1";
+
+        let ident = |name: &str| -> Expr { ExprKind::Ident(Ident::Single(name.into())).into() };
+        let order = |expr, desc, nulls_first| OrderByExpr { expr, desc, nulls_first };
+        let have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        let index = |s: &Statement| match &s.kind {
+            StatementKind::CreateIndex(index) => index.clone(),
+            kind => panic!("want create index, have: {kind:?}"),
+        };
+        let view = |s: &Statement| match &s.kind {
+            StatementKind::CreateView(view) => view.clone(),
+            kind => panic!("want create view, have: {kind:?}"),
+        };
+        let (i1, i2, v1, v2) = (index(&have[0]), index(&have[1]), view(&have[2]), view(&have[3]));
+
+        let want = CreateIndex {
+            name: "i1".into(),
+            unique: true,
+            if_not_exists: true,
+            table: Ident::Compound(vec!["s1".into(), "t1".into()]),
+            columns: vec![order(ident("c1"), false, None), order(ident("c2"), true, None)],
+            filter: Some(
+                ExprKind::BinaryOp {
+                    left: Box::new(ident("c1")),
+                    op: Op::Gt,
+                    right: Box::new(ExprKind::Value(Value::Number("0".into())).into()),
+                }
+                .into(),
+            ),
+        };
+        assert_eq!(want, i1);
+
+        let lower = ExprKind::Function {
+            name: Ident::Single("lower".into()),
+            args: vec![ident("c3")],
+            distinct: false,
+            filter: None,
+        };
+        assert!(!i2.unique);
+        assert_eq!(vec![order(lower.into(), false, Some(true))], i2.columns);
+
+        assert!(v1.or_replace);
+        assert_eq!(vec![Name::from("a")], v1.columns);
+        assert_eq!("SELECT c1 FROM t1 LIMIT 1", v1.query.to_string());
+
+        assert!(!v2.or_replace);
+        assert_eq!(Ident::Compound(vec!["s1".into(), "v2".into()]), v2.name);
+        assert!(v2.columns.is_empty());
+
+        let have = Parser::new("create t1 (c1 int)", &GenericDialect)
+            .and_then(|mut p| p.parse())
+            .unwrap_err();
+        assert_eq!(
+            "1:8: unexpected `t1`, expected one of: `TABLE`, `UNIQUE`, `INDEX`, `OR`, `VIEW`",
+            have.to_string()
+        );
+    }
+
+    #[test]
+    fn test_alter_table() {
+        let input = "
+            ALTER TABLE IF EXISTS s1.t1
+                ADD COLUMN c1 INT NOT NULL,
+                ADD IF NOT EXISTS c2 TEXT,
+                ADD CONSTRAINT u1 UNIQUE (c1),
+                DROP COLUMN IF EXISTS c3 CASCADE,
+                DROP c4 RESTRICT,
+                DROP CONSTRAINT u2,
+                RENAME COLUMN c5 TO c6,
+                RENAME c7 TO c8,
+                ALTER COLUMN c1 SET DEFAULT 0,
+                ALTER c1 DROP DEFAULT,
+                ALTER c2 SET NOT NULL,
+                ALTER c2 DROP NOT NULL,
+                ALTER c2 SET DATA TYPE VARCHAR(10),
+                ALTER c2 TYPE TEXT,
+                RENAME TO t2";
+
+        let column = |name: &str, ty, constraints| ColumnDef { ty, name: name.into(), constraints };
+        let alter = |name: &str, op| AlterTableOperation::AlterColumn { name: name.into(), op };
+        let want: Vec<Statement> = vec![StatementKind::AlterTable(AlterTable {
+            name: Ident::Compound(vec!["s1".into(), "t1".into()]),
+            if_exists: true,
+            operations: vec![
+                AlterTableOperation::AddColumn {
+                    if_not_exists: false,
+                    column: column(
+                        "c1",
+                        ColumnType::Int,
+                        vec![ColumnConstraint { name: None, kind: ColumnConstraintKind::NotNull }],
+                    ),
+                },
+                AlterTableOperation::AddColumn {
+                    if_not_exists: true,
+                    column: column("c2", ColumnType::Text, vec![]),
+                },
+                AlterTableOperation::AddConstraint(TableConstraint {
+                    name: Some("u1".into()),
+                    kind: TableConstraintKind::Unique(vec!["c1".into()]),
+                }),
+                AlterTableOperation::DropColumn {
+                    name: "c3".into(),
+                    if_exists: true,
+                    cascade: true,
+                },
+                AlterTableOperation::DropColumn {
+                    name: "c4".into(),
+                    if_exists: false,
+                    cascade: false,
+                },
+                AlterTableOperation::DropConstraint {
+                    name: "u2".into(),
+                    if_exists: false,
+                    cascade: false,
+                },
+                AlterTableOperation::RenameColumn { from: "c5".into(), to: "c6".into() },
+                AlterTableOperation::RenameColumn { from: "c7".into(), to: "c8".into() },
+                alter(
+                    "c1",
+                    AlterColumnOperation::SetDefault(
+                        ExprKind::Value(Value::Number("0".into())).into(),
+                    ),
+                ),
+                alter("c1", AlterColumnOperation::DropDefault),
+                alter("c2", AlterColumnOperation::SetNotNull),
+                alter("c2", AlterColumnOperation::DropNotNull),
+                alter("c2", AlterColumnOperation::SetType(ColumnType::Varchar(10))),
+                alter("c2", AlterColumnOperation::SetType(ColumnType::Text)),
+                AlterTableOperation::RenameTo(Ident::Single("t2".into())),
+            ],
+        })
+        .into()];
+
+        let have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        assert_eq!(want, have);
+
+        let have = Parser::new("alter table t1 alter c1 set", &GenericDialect)
+            .and_then(|mut p| p.parse())
+            .unwrap_err();
+        assert_eq!(
+            "1:28: unexpected EOF, expected one of: `NOT`, `DEFAULT`, `DATA`",
+            have.to_string()
+        );
+    }
+
+    #[test]
+    fn test_drop_and_truncate() {
+        let input = "
+            DROP TABLE t1;
+            DROP TABLE IF EXISTS t1, s1.t2 CASCADE;
+            DROP INDEX IF EXISTS i1 RESTRICT;
+            DROP VIEW v1;
+            TRUNCATE t1;
+            TRUNCATE TABLE t1, t2 CASCADE";
+
+        let single = |name: &str| Ident::Single(name.into());
+        let drop = |object, if_exists, names, cascade| {
+            StatementKind::Drop(Drop { object, if_exists, names, cascade }).into()
+        };
+        let truncate =
+            |tables, cascade| StatementKind::Truncate(Truncate { tables, cascade }).into();
+        let want: Vec<Statement> = vec![
+            drop(ObjectType::Table, false, vec![single("t1")], false),
+            drop(
+                ObjectType::Table,
+                true,
+                vec![single("t1"), Ident::Compound(vec!["s1".into(), "t2".into()])],
+                true,
+            ),
+            drop(ObjectType::Index, true, vec![single("i1")], false),
+            drop(ObjectType::View, false, vec![single("v1")], false),
+            truncate(vec![single("t1")], false),
+            truncate(vec![single("t1"), single("t2")], true),
+        ];
+
+        let have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        assert_eq!(want, have);
+
+        let have = Parser::new("drop t1", &GenericDialect).and_then(|mut p| p.parse()).unwrap_err();
+        assert_eq!(
+            "1:6: unexpected `t1`, expected one of: `TABLE`, `INDEX`, `VIEW`",
+            have.to_string()
+        );
+    }
+
+    #[test]
+    fn test_column_types() {
+        let input = "
+            CREATE TABLE t1 (
+                c1 SMALLINT, c2 INTEGER, c3 BIGINT, c4 BOOL, c5 REAL, c6 DOUBLE PRECISION,
+                c7 NUMERIC, c8 DECIMAL(10), c9 DECIMAL(10, 2), c10 CHAR, c11 CHAR(3),
+                c12 VARCHAR(70000), c13 TEXT, c14 DATE, c15 TIME WITHOUT TIME ZONE,
+                c16 TIMESTAMP WITH TIME ZONE, c17 BLOB, c18 BYTEA, c19 JSON, c20 INT[][],
+                c21 TEXT ARRAY, c22 geometry(point, 4326), c23 enum('a', 'b''c'), c24 citext
+            )";
+
+        let want = [
+            ColumnType::SmallInt,
+            ColumnType::Int,
+            ColumnType::BigInt,
+            ColumnType::Boolean,
+            ColumnType::Real,
+            ColumnType::Double,
+            ColumnType::Decimal { precision: None, scale: None },
+            ColumnType::Decimal { precision: Some(10), scale: None },
+            ColumnType::Decimal { precision: Some(10), scale: Some(2) },
+            ColumnType::Char(None),
+            ColumnType::Char(Some(3)),
+            ColumnType::Varchar(70000),
+            ColumnType::Text,
+            ColumnType::Date,
+            ColumnType::Time { with_time_zone: false },
+            ColumnType::Timestamp { with_time_zone: true },
+            ColumnType::Blob,
+            ColumnType::Bytea,
+            ColumnType::Json,
+            ColumnType::Array(Box::new(ColumnType::Array(Box::new(ColumnType::Int)))),
+            ColumnType::Array(Box::new(ColumnType::Text)),
+            ColumnType::Custom("geometry".into(), vec!["point".into(), "4326".into()]),
+            ColumnType::Custom("enum".into(), vec!["'a'".into(), "'b''c'".into()]),
+            ColumnType::Custom("citext".into(), vec![]),
+        ];
+
+        let have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        let StatementKind::Create(create) = &have[0].kind else {
+            panic!("want create, have: {have:?}");
+        };
+        let have: Vec<_> = create.columns.iter().map(|c| c.ty.clone()).collect();
+        assert_eq!(Vec::from(want), have);
+
+        let cases = [
+            ("create table t1 (c1 varchar(4294967296))", "1:29: number out of range: 4294967296"),
+            ("create table t1 (c1 decimal(10, 1.5))", "1:33: unexpected `1.5`"),
+            ("create table t1 (c1 varchar)", "1:28: unexpected `)`, expected `(`"),
+        ];
+        for (input, want) in cases {
+            let have = Parser::new(input, &GenericDialect).and_then(|mut p| p.parse()).unwrap_err();
+            assert_eq!(want, have.to_string(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_create_constraints() {
+        let input = "
+            CREATE TABLE IF NOT EXISTS t1 (
+                id INT PRIMARY KEY,
+                c1 VARCHAR(10) NOT NULL DEFAULT 'a' UNIQUE,
+                c2 INT NULL CONSTRAINT positive CHECK (c2 > 0),
+                c3 INT REFERENCES t2 (id) ON DELETE CASCADE ON UPDATE SET NULL,
+                CONSTRAINT pk PRIMARY KEY (id, c1),
+                UNIQUE (c2),
+                FOREIGN KEY (c2, c3) REFERENCES t3 ON UPDATE NO ACTION,
+                CHECK (c2 < c3)
+            )";
+
+        let column = |name: &str, ty, kinds: Vec<ColumnConstraintKind>| ColumnDef {
+            ty,
+            name: name.into(),
+            constraints: kinds
+                .into_iter()
+                .map(|kind| ColumnConstraint { name: None, kind })
+                .collect(),
+        };
+        let compare = |left: &str, op, right: &str| -> Expr {
+            ExprKind::BinaryOp {
+                left: Box::new(ExprKind::Ident(Ident::Single(left.into())).into()),
+                op,
+                right: Box::new(
+                    right
+                        .parse::<i64>()
+                        .map_or_else(
+                            |_| ExprKind::Ident(Ident::Single(right.into())),
+                            |_| ExprKind::Value(Value::Number(right.into())),
+                        )
+                        .into(),
+                ),
             }
+            .into()
+        };
+
+        let mut c2 = column("c2", ColumnType::Int, vec![ColumnConstraintKind::Null]);
+        c2.constraints.push(ColumnConstraint {
+            name: Some("positive".into()),
+            kind: ColumnConstraintKind::Check(compare("c2", Op::Gt, "0")),
+        });
+
+        let want: Vec<Statement> = vec![StatementKind::Create(Create {
+            name: Ident::Single("t1".into()),
+            if_not_exists: true,
+            columns: vec![
+                column("id", ColumnType::Int, vec![ColumnConstraintKind::PrimaryKey]),
+                column(
+                    "c1",
+                    ColumnType::Varchar(10),
+                    vec![
+                        ColumnConstraintKind::NotNull,
+                        ColumnConstraintKind::Default(
+                            ExprKind::Value(Value::String("a".into())).into(),
+                        ),
+                        ColumnConstraintKind::Unique,
+                    ],
+                ),
+                c2,
+                column(
+                    "c3",
+                    ColumnType::Int,
+                    vec![ColumnConstraintKind::References(References {
+                        table: Ident::Single("t2".into()),
+                        columns: vec!["id".into()],
+                        on_delete: Some(ReferentialAction::Cascade),
+                        on_update: Some(ReferentialAction::SetNull),
+                    })],
+                ),
+            ],
+            constraints: vec![
+                TableConstraint {
+                    name: Some("pk".into()),
+                    kind: TableConstraintKind::PrimaryKey(vec!["id".into(), "c1".into()]),
+                },
+                TableConstraint {
+                    name: None,
+                    kind: TableConstraintKind::Unique(vec!["c2".into()]),
+                },
+                TableConstraint {
+                    name: None,
+                    kind: TableConstraintKind::ForeignKey {
+                        columns: vec!["c2".into(), "c3".into()],
+                        references: References {
+                            table: Ident::Single("t3".into()),
+                            columns: vec![],
+                            on_delete: None,
+                            on_update: Some(ReferentialAction::NoAction),
+                        },
+                    },
+                },
+                TableConstraint {
+                    name: None,
+                    kind: TableConstraintKind::Check(compare("c2", Op::Lt, "c3")),
+                },
+            ],
+        })
+        .into()];
+
+        let have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        assert_eq!(want, have);
+
+        // A column's DEFAULT ends before a following NOT NULL
+        let input = "create table t1 (c1 int default 1 + 2 not null)";
+        let have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        assert_eq!("CREATE TABLE t1 (c1 INT DEFAULT 1 + 2 NOT NULL)", have[0].to_string());
+
+        let input = "create table t1 (c1 int, constraint c2 int)";
+        let have = Parser::new(input, &GenericDialect).and_then(|mut p| p.parse()).unwrap_err();
+        assert_eq!(
+            "1:40: unexpected `INT`, expected one of: `PRIMARY`, `UNIQUE`, `FOREIGN`, `CHECK`",
+            have.to_string()
+        );
+    }
+
+    #[test]
+    fn test_typed_numbers() {
+        let parse = |input: &str, mode| -> Result<Expr, ParserError> {
+            let mut parser = Parser::new(input, &GenericDialect)?.number_mode(mode);
+            parser.parse_expr(0)
+        };
+        let value = |v| Expr::from(ExprKind::Value(v));
+
+        let cases = [
+            ("42", NumberMode::Text, Value::Number("42".into())),
+            ("1_000", NumberMode::Float, Value::Int(1000)),
+            ("0xFF", NumberMode::Float, Value::Int(255)),
+            ("0b101", NumberMode::Decimal, Value::Int(5)),
+            ("-9223372036854775808", NumberMode::Float, Value::Int(i64::MIN)),
+            ("1.5", NumberMode::Float, Value::Float(1.5)),
+            ("-1e-3", NumberMode::Float, Value::Float(-0.001)),
+            ("1.50", NumberMode::Decimal, Value::Decimal(Decimal { mantissa: 15, scale: 1 })),
+            (".5e-2", NumberMode::Decimal, Value::Decimal(Decimal { mantissa: 5, scale: 3 })),
+            ("-1.5E3", NumberMode::Decimal, Value::Decimal(Decimal { mantissa: -1500, scale: 0 })),
+            (
+                "0e-4000000000",
+                NumberMode::Decimal,
+                Value::Decimal(Decimal { mantissa: 0, scale: 0 }),
+            ),
+            ("1000e-40", NumberMode::Decimal, Value::Decimal(Decimal { mantissa: 1, scale: 37 })),
+            ("X'0a'", NumberMode::Float, Value::HexString("0a".into())),
+        ];
+        for (input, mode, want) in cases {
+            assert_eq!(value(want), parse(input, mode).unwrap(), "{input}");
         }
 
-        Ok(())
+        // Without typing, a minus is a unary operator
+        let want = ExprKind::UnaryOp {
+            op: UnaryOp::Minus,
+            expr: Box::new(value(Value::Number("1".into()))),
+        };
+        assert_eq!(Expr::from(want), parse("-1", NumberMode::Text).unwrap());
+
+        let cases = [
+            (
+                "9223372036854775808",
+                NumberMode::Float,
+                "1:1: number out of range: 9223372036854775808",
+            ),
+            (
+                "1 + -0x8000000000000001",
+                NumberMode::Decimal,
+                "1:5: number out of range: -0x8000000000000001",
+            ),
+            ("1e999", NumberMode::Float, "1:1: number out of range: 1e999"),
+            ("1e99", NumberMode::Decimal, "1:1: number out of range: 1e99"),
+            (
+                "1e-9223372036854775808",
+                NumberMode::Decimal,
+                "1:1: number out of range: 1e-9223372036854775808",
+            ),
+            ("1e-39", NumberMode::Decimal, "1:1: number out of range: 1e-39"),
+        ];
+        for (input, mode, want) in cases {
+            assert_eq!(want, parse(input, mode).unwrap_err().to_string(), "{input}");
+        }
+
+        let have = parse(
+            "1 +
+  99999999999999999999",
+            NumberMode::Float,
+        )
+        .unwrap_err();
+        assert_eq!(
+            Span {
+                start: Location { line: 1, col: 2, offset: 6 },
+                end: Location { line: 1, col: 22, offset: 26 },
+            },
+            have.span()
+        );
+    }
+
+    #[test]
+    fn test_select_statement() {
+        let input = "
+            SELECT * FROM t1
+            ORDER BY c1, c2 DESC NULLS LAST, c3 ASC NULLS FIRST
+            LIMIT 10 OFFSET 20;
+            SELECT * FROM t1 LIMIT 20, 10";
+
+        let body = || Query {
+            projection: vec![SelectItemKind::Wildcard.into()],
+            from: FromTableKind::Table { name: Ident::Single("t1".into()), alias: None }.into(),
+            joins: vec![],
+            filter: None,
+            group: vec![],
+            having: None,
+        };
+
+        let want: Vec<Statement> = vec![
+            StatementKind::Select(Select {
+                body: body(),
+                order: vec![
+                    OrderByExpr {
+                        expr: ExprKind::Ident(Ident::Single("c1".into())).into(),
+                        desc: false,
+                        nulls_first: None,
+                    },
+                    OrderByExpr {
+                        expr: ExprKind::Ident(Ident::Single("c2".into())).into(),
+                        desc: true,
+                        nulls_first: Some(false),
+                    },
+                    OrderByExpr {
+                        expr: ExprKind::Ident(Ident::Single("c3".into())).into(),
+                        desc: false,
+                        nulls_first: Some(true),
+                    },
+                ],
+                limit: Some(ExprKind::Value(Value::Number("10".into())).into()),
+                offset: Some(ExprKind::Value(Value::Number("20".into())).into()),
+            })
+            .into(),
+            StatementKind::Select(Select {
+                body: body(),
+                order: vec![],
+                limit: Some(ExprKind::Value(Value::Number("10".into())).into()),
+                offset: Some(ExprKind::Value(Value::Number("20".into())).into()),
+            })
+            .into(),
+        ];
+
+        let have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        assert_eq!(want, have)
     }
 
-    fn parse_tokens(&mut self, tokens: &[Token]) -> Result<()> {
-        for want in tokens {
-            let TokenWithLocation(ref have, location) = self.next();
-            if want == have {
-                continue;
-            }
-
-            Err(Unexpected(&have, &location))?;
-        }
+    #[test]
+    fn test_group_by_having() {
+        let input = "select * from t1 group by c1, c2 having c1 > 5";
+
+        let want = Query {
+            projection: vec![SelectItemKind::Wildcard.into()],
+            from: FromTableKind::Table { name: Ident::Single("t1".into()), alias: None }.into(),
+            joins: vec![],
+            filter: None,
+            group: vec![
+                ExprKind::Ident(Ident::Single("c1".into())).into(),
+                ExprKind::Ident(Ident::Single("c2".into())).into(),
+            ],
+            having: Some(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
+                    op: Op::Gt,
+                    right: Box::new(ExprKind::Value(Value::Number("5".into())).into()),
+                }
+                .into(),
+            ),
+        };
 
-        Ok(())
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_query().unwrap();
+        assert_eq!(want, have)
     }
 
-    fn next(&mut self) -> TokenWithLocation {
-        self.index += 1;
-        self.get(self.index - 1)
-    }
+    #[test]
+    fn test_group_by_grouping_sets() {
+        let input = "
+            select * from t1
+            group by c1, grouping sets ((c1, c2), c3, ()), rollup (c1, (c2, c3)), cube (c1)";
 
-    fn peek(&self) -> TokenWithLocation {
-        self.peek_n(0)
-    }
+        let ident = |s: &str| ExprKind::Ident(Ident::Single(s.into())).into();
+        let want = vec![
+            ident("c1"),
+            ExprKind::GroupingSets(vec![vec![ident("c1"), ident("c2")], vec![ident("c3")], vec![]])
+                .into(),
+            ExprKind::Rollup(vec![vec![ident("c1")], vec![ident("c2"), ident("c3")]]).into(),
+            ExprKind::Cube(vec![vec![ident("c1")]]).into(),
+        ];
 
-    fn peek_n(&self, n: usize) -> TokenWithLocation {
-        self.get(self.index + n)
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_query().unwrap().group;
+        assert_eq!(want, have)
     }
 
-    fn get(&self, i: usize) -> TokenWithLocation {
-        self.tokens
-            .get(i)
-            .map(|t| t.clone())
-            .unwrap_or(TokenWithLocation(Token::Eof, Default::default()))
+    #[test]
+    fn test_non_reserved_keywords() {
+        let input = "select first from t1 order by last nulls first";
+
+        let want = Select {
+            body: Query {
+                projection: vec![SelectItemKind::Expr(
+                    ExprKind::Ident(Ident::Single("first".into())).into(),
+                )
+                .into()],
+                from: FromTableKind::Table { name: Ident::Single("t1".into()), alias: None }.into(),
+                joins: vec![],
+                filter: None,
+                group: vec![],
+                having: None,
+            },
+            order: vec![OrderByExpr {
+                expr: ExprKind::Ident(Ident::Single("last".into())).into(),
+                desc: false,
+                nulls_first: Some(true),
+            }],
+            limit: None,
+            offset: None,
+        };
+
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_select().unwrap();
+        assert_eq!(want, have);
+
+        // Keywords keep the case they're written in, like any other identifier
+        let input = "select Type, FIRST from t1";
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_query().unwrap();
+        let ident = |name: &str| -> SelectItem {
+            SelectItemKind::Expr(ExprKind::Ident(Ident::Single(name.into())).into()).into()
+        };
+        assert_eq!(vec![ident("Type"), ident("FIRST")], have.projection);
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::parser::{FromTable, Join, JoinConstraint, JoinType};
+    #[test]
+    fn test_dialects() {
+        let input = "select \"c1\" || 'c2' from t1";
+
+        let want = || -> SelectItem {
+            SelectItemKind::Expr(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Ident(Ident::Single(Name::quoted("c1"))).into()),
+                    op: Op::Concat,
+                    right: Box::new(ExprKind::Value(Value::String("c2".into())).into()),
+                }
+                .into(),
+            )
+            .into()
+        };
+        for dialect in [&GenericDialect as &dyn Dialect, &PostgresDialect, &SqliteDialect] {
+            let mut have = Parser::new(input, dialect).unwrap().parse_query().unwrap().projection;
+            assert_eq!(want(), have.remove(0), "{dialect:?}");
+        }
 
-    use super::{
-        ColumnDef, ColumnType, Create, Delete, Expr, Ident, Insert, Op, Parser, Query, SelectItem,
-        Set, Statement, Update, Value,
-    };
+        assert!(Parser::new(input, &MySqlDialect).unwrap().parse().is_err());
+    }
 
     #[test]
-    fn test_create_statement() {
-        let input = "
-            CREATE TABLE t1 (
-                c1 INT,
-                c2 VARCHAR(1024)
-            )";
+    fn test_dialect_statements() {
+        #[derive(Debug)]
+        struct ReadOnlyDialect;
 
-        let want = vec![Statement::Create(Create {
-            name: "t1".into(),
-            columns: vec![
-                ColumnDef { ty: ColumnType::Int, name: "c1".into() },
-                ColumnDef { ty: ColumnType::Varchar(1024), name: "c2".into() },
-            ],
-        })];
+        impl Dialect for ReadOnlyDialect {
+            fn quote_style(&self) -> QuoteStyle {
+                QuoteStyle::Ansi
+            }
 
-        let have = Parser::new(input).unwrap().parse().unwrap();
-        assert_eq!(want, have)
+            fn supports_statement(&self, kw: Keyword) -> bool {
+                kw == Keyword::Select
+            }
+        }
+
+        assert!(Parser::new("select * from t1", &ReadOnlyDialect).unwrap().parse().is_ok());
+        assert!(Parser::new("delete from t1", &ReadOnlyDialect).unwrap().parse().is_err());
     }
 
     #[test]
     fn test_parse_projection() {
         let input = "t1.*, *, s1.t1.c1";
 
-        let want = vec![
-            SelectItem::QualifiedWildcard(vec!["t1".into()]),
-            SelectItem::Wildcard,
-            SelectItem::Expr(Expr::Ident(Ident::Compound(vec![
-                "s1".into(),
-                "t1".into(),
-                "c1".into(),
-            ]))),
+        let want: Vec<SelectItem> = vec![
+            SelectItemKind::QualifiedWildcard(vec!["t1".into()]).into(),
+            SelectItemKind::Wildcard.into(),
+            SelectItemKind::Expr(
+                ExprKind::Ident(Ident::Compound(vec!["s1".into(), "t1".into(), "c1".into()]))
+                    .into(),
+            )
+            .into(),
         ];
-        let have = Parser::new(input).unwrap().parse_projection().unwrap();
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_projection().unwrap();
         assert_eq!(want, have)
     }
 
@@ -810,9 +2404,9 @@ mod test {
         ($name:tt, $input:expr, $want:expr) => {
             #[test]
             fn $name() {
-                let mut parser = Parser::new($input).unwrap();
+                let mut parser = Parser::new($input, &GenericDialect).unwrap();
                 let have = parser.parse_expr(0).unwrap();
-                assert_eq!($want, have);
+                assert_eq!(Expr::from($want), have);
             }
         };
     }
@@ -820,184 +2414,442 @@ mod test {
     test_parse_expr!(
         test_expr_binary_op,
         "c1 < 5",
-        Expr::BinaryOp {
-            left: Box::new(Expr::Ident(Ident::Single("c1".into()))),
+        ExprKind::BinaryOp {
+            left: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
             op: Op::Lt,
-            right: Box::new(Expr::Value(Value::Number("5".into()))),
+            right: Box::new(ExprKind::Value(Value::Number("5".into())).into()),
         }
     );
 
     test_parse_expr!(
         test_expr_binary_op_in,
-        "c1 < 5 and c2 in (1, \"2\", 3, \"4\")",
-        Expr::BinaryOp {
-            left: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Ident(Ident::Single("c1".into()))),
-                op: Op::Lt,
-                right: Box::new(Expr::Value(Value::Number("5".into()))),
-            }),
+        "c1 < 5 and c2 in (1, '2', 3, '4')",
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
+                    op: Op::Lt,
+                    right: Box::new(ExprKind::Value(Value::Number("5".into())).into()),
+                }
+                .into()
+            ),
             op: Op::And,
-            right: Box::new(Expr::InList {
-                expr: Box::new(Expr::Ident(Ident::Single("c2".into()))),
-                list: vec![
-                    Expr::Value(Value::Number("1".into())),
-                    Expr::Value(Value::String("2".into())),
-                    Expr::Value(Value::Number("3".into())),
-                    Expr::Value(Value::String("4".into())),
-                ],
-                negated: false,
-            }),
+            right: Box::new(
+                ExprKind::InList {
+                    expr: Box::new(ExprKind::Ident(Ident::Single("c2".into())).into()),
+                    list: vec![
+                        ExprKind::Value(Value::Number("1".into())).into(),
+                        ExprKind::Value(Value::String("2".into())).into(),
+                        ExprKind::Value(Value::Number("3".into())).into(),
+                        ExprKind::Value(Value::String("4".into())).into(),
+                    ],
+                    negated: false,
+                }
+                .into()
+            ),
         }
     );
 
     test_parse_expr!(
         test_expr_binary_op_not_in,
-        "c1 < 5 and c2 not in (1, \"2\", 3, \"4\")",
-        Expr::BinaryOp {
-            left: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Ident(Ident::Single("c1".into()))),
-                op: Op::Lt,
-                right: Box::new(Expr::Value(Value::Number("5".into()))),
-            }),
+        "c1 < 5 and c2 not in (1, '2', 3, '4')",
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
+                    op: Op::Lt,
+                    right: Box::new(ExprKind::Value(Value::Number("5".into())).into()),
+                }
+                .into()
+            ),
             op: Op::And,
-            right: Box::new(Expr::InList {
-                expr: Box::new(Expr::Ident(Ident::Single("c2".into()))),
-                list: vec![
-                    Expr::Value(Value::Number("1".into())),
-                    Expr::Value(Value::String("2".into())),
-                    Expr::Value(Value::Number("3".into())),
-                    Expr::Value(Value::String("4".into())),
-                ],
-                negated: true,
-            }),
+            right: Box::new(
+                ExprKind::InList {
+                    expr: Box::new(ExprKind::Ident(Ident::Single("c2".into())).into()),
+                    list: vec![
+                        ExprKind::Value(Value::Number("1".into())).into(),
+                        ExprKind::Value(Value::String("2".into())).into(),
+                        ExprKind::Value(Value::Number("3".into())).into(),
+                        ExprKind::Value(Value::String("4".into())).into(),
+                    ],
+                    negated: true,
+                }
+                .into()
+            ),
         }
     );
 
     test_parse_expr!(
         test_expr_binary_op_not_in_parens,
-        "(c1 < 5) and (c2 not in (1, \"2\", 3, \"4\"))",
-        Expr::BinaryOp {
-            left: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Ident(Ident::Single("c1".into()))),
-                op: Op::Lt,
-                right: Box::new(Expr::Value(Value::Number("5".into()))),
-            }),
+        "(c1 < 5) and (c2 not in (1, '2', 3, '4'))",
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
+                    op: Op::Lt,
+                    right: Box::new(ExprKind::Value(Value::Number("5".into())).into()),
+                }
+                .into()
+            ),
             op: Op::And,
-            right: Box::new(Expr::InList {
-                expr: Box::new(Expr::Ident(Ident::Single("c2".into()))),
-                list: vec![
-                    Expr::Value(Value::Number("1".into())),
-                    Expr::Value(Value::String("2".into())),
-                    Expr::Value(Value::Number("3".into())),
-                    Expr::Value(Value::String("4".into())),
-                ],
-                negated: true,
-            }),
+            right: Box::new(
+                ExprKind::InList {
+                    expr: Box::new(ExprKind::Ident(Ident::Single("c2".into())).into()),
+                    list: vec![
+                        ExprKind::Value(Value::Number("1".into())).into(),
+                        ExprKind::Value(Value::String("2".into())).into(),
+                        ExprKind::Value(Value::Number("3".into())).into(),
+                        ExprKind::Value(Value::String("4".into())).into(),
+                    ],
+                    negated: true,
+                }
+                .into()
+            ),
         }
     );
 
     test_parse_expr!(
         test_expr_parens,
         "c1 < (5 < c2) AND (c1 < 5) < c2",
-        Expr::BinaryOp {
-            left: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Ident(Ident::Single("c1".into()))),
-                op: Op::Lt,
-                right: Box::new(Expr::BinaryOp {
-                    left: Box::new(Expr::Value(Value::Number("5".into()))),
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
                     op: Op::Lt,
-                    right: Box::new(Expr::Ident(Ident::Single("c2".into())))
-                })
-            }),
+                    right: Box::new(
+                        ExprKind::BinaryOp {
+                            left: Box::new(ExprKind::Value(Value::Number("5".into())).into()),
+                            op: Op::Lt,
+                            right: Box::new(ExprKind::Ident(Ident::Single("c2".into())).into())
+                        }
+                        .into()
+                    )
+                }
+                .into()
+            ),
             op: Op::And,
-            right: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::BinaryOp {
-                    left: Box::new(Expr::Ident(Ident::Single("c1".into()))),
+            right: Box::new(
+                ExprKind::BinaryOp {
+                    left: Box::new(
+                        ExprKind::BinaryOp {
+                            left: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
+                            op: Op::Lt,
+                            right: Box::new(ExprKind::Value(Value::Number("5".into())).into())
+                        }
+                        .into()
+                    ),
                     op: Op::Lt,
-                    right: Box::new(Expr::Value(Value::Number("5".into())))
-                }),
-                op: Op::Lt,
-                right: Box::new(Expr::Ident(Ident::Single("c2".into())))
-            })
+                    right: Box::new(ExprKind::Ident(Ident::Single("c2".into())).into())
+                }
+                .into()
+            )
         }
     );
 
     test_parse_expr!(
         test_expr_between,
         "c1 between 0 and 200",
-        Expr::Between {
-            expr: Box::new(Expr::Ident(Ident::Single("c1".into()))),
+        ExprKind::Between {
+            expr: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
             negated: false,
-            low: Box::new(Expr::Value(Value::Number("0".into()))),
-            high: Box::new(Expr::Value(Value::Number("200".into()))),
+            low: Box::new(ExprKind::Value(Value::Number("0".into())).into()),
+            high: Box::new(ExprKind::Value(Value::Number("200".into())).into()),
         }
     );
 
     test_parse_expr!(
         test_expr_not_between,
         "c1 not between 0 and 200",
-        Expr::Between {
-            expr: Box::new(Expr::Ident(Ident::Single("c1".into()))),
+        ExprKind::Between {
+            expr: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
             negated: true,
-            low: Box::new(Expr::Value(Value::Number("0".into()))),
-            high: Box::new(Expr::Value(Value::Number("200".into()))),
+            low: Box::new(ExprKind::Value(Value::Number("0".into())).into()),
+            high: Box::new(ExprKind::Value(Value::Number("200".into())).into()),
         }
     );
 
     test_parse_expr!(
         test_expr_compound_ident,
         "s1.t1.c1 > 5",
-        Expr::BinaryOp {
-            left: Box::new(Expr::Ident(Ident::Compound(vec![
-                "s1".into(),
-                "t1".into(),
-                "c1".into()
-            ]))),
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::Ident(Ident::Compound(vec!["s1".into(), "t1".into(), "c1".into()]))
+                    .into()
+            ),
             op: Op::Gt,
-            right: Box::new(Expr::Value(Value::Number("5".into()))),
+            right: Box::new(ExprKind::Value(Value::Number("5".into())).into()),
         }
     );
 
     test_parse_expr!(
         test_expr_sub_query,
         "1 < (select * from t1 join t2 using (c1) where t1.c2 > t2.c2)",
-        Expr::BinaryOp {
-            left: Box::new(Expr::Value(Value::Number("1".into()))),
+        ExprKind::BinaryOp {
+            left: Box::new(ExprKind::Value(Value::Number("1".into())).into()),
             op: Op::Lt,
-            right: Box::new(Expr::SubQuery(Box::new(Query {
-                projection: vec![SelectItem::Wildcard],
-                from: FromTable::Table { name: vec!["t1".into()], alias: None },
-                joins: vec![Join {
-                    from: FromTable::Table { name: vec!["t2".into()], alias: None },
-                    ty: JoinType::Inner,
-                    constraint: JoinConstraint::Using(vec!["c1".into()])
-                }],
-                filter: Some(Expr::BinaryOp {
-                    left: Box::new(Expr::Ident(Ident::Compound(vec!["t1".into(), "c2".into()]))),
-                    op: Op::Gt,
-                    right: Box::new(Expr::Ident(Ident::Compound(vec!["t2".into(), "c2".into()])))
-                }),
-                group: vec![]
-            }))),
+            right: Box::new(
+                ExprKind::SubQuery(Box::new(Query {
+                    projection: vec![SelectItemKind::Wildcard.into()],
+                    from: FromTableKind::Table { name: Ident::Single("t1".into()), alias: None }
+                        .into(),
+                    joins: vec![Join {
+                        from: FromTableKind::Table {
+                            name: Ident::Single("t2".into()),
+                            alias: None
+                        }
+                        .into(),
+                        ty: JoinType::Inner,
+                        constraint: JoinConstraint::Using(vec!["c1".into()]),
+                        span: Span::default()
+                    }],
+                    filter: Some(
+                        ExprKind::BinaryOp {
+                            left: Box::new(
+                                ExprKind::Ident(Ident::Compound(vec!["t1".into(), "c2".into()]))
+                                    .into()
+                            ),
+                            op: Op::Gt,
+                            right: Box::new(
+                                ExprKind::Ident(Ident::Compound(vec!["t2".into(), "c2".into()]))
+                                    .into()
+                            )
+                        }
+                        .into()
+                    ),
+                    group: vec![],
+                    having: None
+                }))
+                .into()
+            ),
         }
     );
 
     test_parse_expr!(
         test_expr_is_null,
         "c1 is not null and c2 is null",
-        Expr::BinaryOp {
-            left: Box::new(Expr::IsNotNull(Box::new(Expr::Ident(Ident::Single("c1".into()))))),
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::IsNotNull(Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()))
+                    .into()
+            ),
+            op: Op::And,
+            right: Box::new(
+                ExprKind::IsNull(Box::new(ExprKind::Ident(Ident::Single("c2".into())).into()))
+                    .into()
+            ),
+        }
+    );
+
+    test_parse_expr!(
+        test_expr_function,
+        "count(*) > lower(c1)",
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::Function {
+                    name: Ident::Single("count".into()),
+                    args: vec![ExprKind::Wildcard.into()],
+                    distinct: false,
+                    filter: None,
+                }
+                .into()
+            ),
+            op: Op::Gt,
+            right: Box::new(
+                ExprKind::Function {
+                    name: Ident::Single("lower".into()),
+                    args: vec![ExprKind::Ident(Ident::Single("c1".into())).into()],
+                    distinct: false,
+                    filter: None,
+                }
+                .into()
+            ),
+        }
+    );
+
+    test_parse_expr!(
+        test_expr_function_distinct_filter,
+        "count(distinct t1.c1) filter (where c2 is not null)",
+        ExprKind::Function {
+            name: Ident::Single("count".into()),
+            args: vec![ExprKind::Ident(Ident::Compound(vec!["t1".into(), "c1".into()])).into()],
+            distinct: true,
+            filter: Some(Box::new(
+                ExprKind::IsNotNull(Box::new(ExprKind::Ident(Ident::Single("c2".into())).into()))
+                    .into()
+            )),
+        }
+    );
+
+    test_parse_expr!(
+        test_expr_function_args,
+        "s1.coalesce(t1.*, max(c1), 1)",
+        ExprKind::Function {
+            name: Ident::Compound(vec!["s1".into(), "coalesce".into()]),
+            args: vec![
+                ExprKind::QualifiedWildcard(vec!["t1".into()]).into(),
+                ExprKind::Function {
+                    name: Ident::Single("max".into()),
+                    args: vec![ExprKind::Ident(Ident::Single("c1".into())).into()],
+                    distinct: false,
+                    filter: None,
+                }
+                .into(),
+                ExprKind::Value(Value::Number("1".into())).into(),
+            ],
+            distinct: false,
+            filter: None,
+        }
+    );
+
+    test_parse_expr!(
+        test_expr_arithmetic,
+        "price * qty - discount > 0 AND NOT archived",
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::BinaryOp {
+                    left: Box::new(
+                        ExprKind::BinaryOp {
+                            left: Box::new(
+                                ExprKind::BinaryOp {
+                                    left: Box::new(
+                                        ExprKind::Ident(Ident::Single("price".into())).into()
+                                    ),
+                                    op: Op::Multiply,
+                                    right: Box::new(
+                                        ExprKind::Ident(Ident::Single("qty".into())).into()
+                                    ),
+                                }
+                                .into()
+                            ),
+                            op: Op::Minus,
+                            right: Box::new(
+                                ExprKind::Ident(Ident::Single("discount".into())).into()
+                            ),
+                        }
+                        .into()
+                    ),
+                    op: Op::Gt,
+                    right: Box::new(ExprKind::Value(Value::Number("0".into())).into()),
+                }
+                .into()
+            ),
             op: Op::And,
-            right: Box::new(Expr::IsNull(Box::new(Expr::Ident(Ident::Single("c2".into()))))),
+            right: Box::new(
+                ExprKind::UnaryOp {
+                    op: UnaryOp::Not,
+                    expr: Box::new(ExprKind::Ident(Ident::Single("archived".into())).into()),
+                }
+                .into()
+            ),
+        }
+    );
+
+    test_parse_expr!(
+        test_expr_unary,
+        "-a + b % 2 || c / -(d + 1)",
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::BinaryOp {
+                    left: Box::new(
+                        ExprKind::UnaryOp {
+                            op: UnaryOp::Minus,
+                            expr: Box::new(ExprKind::Ident(Ident::Single("a".into())).into()),
+                        }
+                        .into()
+                    ),
+                    op: Op::Plus,
+                    right: Box::new(
+                        ExprKind::BinaryOp {
+                            left: Box::new(ExprKind::Ident(Ident::Single("b".into())).into()),
+                            op: Op::Modulo,
+                            right: Box::new(ExprKind::Value(Value::Number("2".into())).into()),
+                        }
+                        .into()
+                    ),
+                }
+                .into()
+            ),
+            op: Op::Concat,
+            right: Box::new(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Ident(Ident::Single("c".into())).into()),
+                    op: Op::Divide,
+                    right: Box::new(
+                        ExprKind::UnaryOp {
+                            op: UnaryOp::Minus,
+                            expr: Box::new(
+                                ExprKind::BinaryOp {
+                                    left: Box::new(
+                                        ExprKind::Ident(Ident::Single("d".into())).into()
+                                    ),
+                                    op: Op::Plus,
+                                    right: Box::new(
+                                        ExprKind::Value(Value::Number("1".into())).into()
+                                    ),
+                                }
+                                .into()
+                            ),
+                        }
+                        .into()
+                    ),
+                }
+                .into()
+            ),
+        }
+    );
+
+    test_parse_expr!(
+        test_expr_not_precedence,
+        "not a = 1 or b",
+        ExprKind::BinaryOp {
+            left: Box::new(
+                ExprKind::UnaryOp {
+                    op: UnaryOp::Not,
+                    expr: Box::new(
+                        ExprKind::BinaryOp {
+                            left: Box::new(ExprKind::Ident(Ident::Single("a".into())).into()),
+                            op: Op::Eq,
+                            right: Box::new(ExprKind::Value(Value::Number("1".into())).into()),
+                        }
+                        .into()
+                    ),
+                }
+                .into()
+            ),
+            op: Op::Or,
+            right: Box::new(ExprKind::Ident(Ident::Single("b".into())).into()),
         }
     );
 
+    #[test]
+    fn test_parse_select_item_multiply() {
+        let input = "*, c1 * 2, t1.*";
+
+        let want: Vec<SelectItem> = vec![
+            SelectItemKind::Wildcard.into(),
+            SelectItemKind::Expr(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Ident(Ident::Single("c1".into())).into()),
+                    op: Op::Multiply,
+                    right: Box::new(ExprKind::Value(Value::Number("2".into())).into()),
+                }
+                .into(),
+            )
+            .into(),
+            SelectItemKind::QualifiedWildcard(vec!["t1".into()]).into(),
+        ];
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_projection().unwrap();
+        assert_eq!(want, have)
+    }
+
     #[test]
     fn test_parse_from() {
         let input = "table1 as t1";
 
-        let want = FromTable::Table { name: vec!["table1".into()], alias: Some("t1".into()) };
-        let have = Parser::new(input).unwrap().parse_from().unwrap();
+        let want: FromTable =
+            FromTableKind::Table { name: Ident::Single("table1".into()), alias: Some("t1".into()) }
+                .into();
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_from().unwrap();
         assert_eq!(want, have)
     }
 
@@ -1007,21 +2859,30 @@ mod test {
 
         let want = vec![
             Join {
-                from: FromTable::Table { name: vec!["t2".into()], alias: None },
+                from: FromTableKind::Table { name: Ident::Single("t2".into()), alias: None }.into(),
                 ty: JoinType::Inner,
-                constraint: JoinConstraint::On(Expr::BinaryOp {
-                    left: Box::new(Expr::Ident(Ident::Compound(vec!["t1".into(), "c1".into()]))),
-                    op: Op::Eq,
-                    right: Box::new(Expr::Ident(Ident::Compound(vec!["t2".into(), "c1".into()]))),
-                }),
+                constraint: JoinConstraint::On(
+                    ExprKind::BinaryOp {
+                        left: Box::new(
+                            ExprKind::Ident(Ident::Compound(vec!["t1".into(), "c1".into()])).into(),
+                        ),
+                        op: Op::Eq,
+                        right: Box::new(
+                            ExprKind::Ident(Ident::Compound(vec!["t2".into(), "c1".into()])).into(),
+                        ),
+                    }
+                    .into(),
+                ),
+                span: Span::default(),
             },
             Join {
-                from: FromTable::Table { name: vec!["t3".into()], alias: None },
+                from: FromTableKind::Table { name: Ident::Single("t3".into()), alias: None }.into(),
                 ty: JoinType::Inner,
                 constraint: JoinConstraint::Using(vec!["c2".into(), "c3".into()]),
+                span: Span::default(),
             },
         ];
-        let have = Parser::new(input).unwrap().parse_joins().unwrap();
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_joins().unwrap();
         assert_eq!(want, have)
     }
 
@@ -1030,68 +2891,176 @@ mod test {
         let input = "join (select * from t1) t1 using (c1)";
 
         let want = vec![Join {
-            from: FromTable::Derived {
+            from: FromTableKind::Derived {
+                lateral: false,
                 query: Box::new(Query {
-                    projection: vec![SelectItem::Wildcard],
-                    from: FromTable::Table { name: vec!["t1".into()], alias: None },
+                    projection: vec![SelectItemKind::Wildcard.into()],
+                    from: FromTableKind::Table { name: Ident::Single("t1".into()), alias: None }
+                        .into(),
                     joins: vec![],
                     filter: None,
                     group: vec![],
+                    having: None,
                 }),
                 alias: Some("t1".into()),
-            },
+            }
+            .into(),
             ty: JoinType::Inner,
             constraint: JoinConstraint::Using(vec!["c1".into()]),
+            span: Span::default(),
         }];
-        let have = Parser::new(input).unwrap().parse_joins().unwrap();
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_joins().unwrap();
+        assert_eq!(want, have)
+    }
+
+    #[test]
+    fn test_parse_outer_joins() {
+        let input = "
+            left join t2 on true
+            right outer join t3 using (c1)
+            full join t4 using (c1)
+            inner join t5 on false
+            cross join t6
+            natural left join t7, t8
+            natural join lateral (select * from t9) t9";
+
+        let table =
+            |s: &str| FromTableKind::Table { name: Ident::Single(s.into()), alias: None }.into();
+        let using = || JoinConstraint::Using(vec!["c1".into()]);
+        let want = vec![
+            Join {
+                from: table("t2"),
+                ty: JoinType::Left,
+                constraint: JoinConstraint::On(ExprKind::Value(Value::Bool(true)).into()),
+                span: Span::default(),
+            },
+            Join {
+                from: table("t3"),
+                ty: JoinType::Right,
+                constraint: using(),
+                span: Span::default(),
+            },
+            Join {
+                from: table("t4"),
+                ty: JoinType::Full,
+                constraint: using(),
+                span: Span::default(),
+            },
+            Join {
+                from: table("t5"),
+                ty: JoinType::Inner,
+                constraint: JoinConstraint::On(ExprKind::Value(Value::Bool(false)).into()),
+                span: Span::default(),
+            },
+            Join {
+                from: table("t6"),
+                ty: JoinType::Cross,
+                constraint: JoinConstraint::None,
+                span: Span::default(),
+            },
+            Join {
+                from: table("t7"),
+                ty: JoinType::Left,
+                constraint: JoinConstraint::Natural,
+                span: Span::default(),
+            },
+            Join {
+                from: table("t8"),
+                ty: JoinType::Comma,
+                constraint: JoinConstraint::None,
+                span: Span::default(),
+            },
+            Join {
+                from: FromTableKind::Derived {
+                    lateral: true,
+                    query: Box::new(Query {
+                        projection: vec![SelectItemKind::Wildcard.into()],
+                        from: table("t9"),
+                        joins: vec![],
+                        filter: None,
+                        group: vec![],
+                        having: None,
+                    }),
+                    alias: Some("t9".into()),
+                }
+                .into(),
+                ty: JoinType::Inner,
+                constraint: JoinConstraint::Natural,
+                span: Span::default(),
+            },
+        ];
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_joins().unwrap();
         assert_eq!(want, have)
     }
 
+    #[test]
+    fn test_parse_join_without_constraint() {
+        for input in ["join t2", "left join t2 where true", "natural cross join t2"] {
+            assert!(Parser::new(input, &GenericDialect).unwrap().parse_joins().is_err(), "{input}");
+        }
+    }
+
     #[test]
     fn test_parse_insert() {
-        let input = "insert into t1 values ((1, 2), (\"1\", \"2\"))";
+        let input = "insert into t1 values (1, 2), ('1', default)";
 
         let want = Insert {
             table: Ident::Single("t1".into()),
-            rows: vec![
-                vec![
-                    Expr::Value(Value::Number("1".into())),
-                    Expr::Value(Value::Number("2".into())),
-                ],
+            columns: vec![],
+            source: InsertSource::Values(vec![
                 vec![
-                    Expr::Value(Value::String("1".into())),
-                    Expr::Value(Value::String("2".into())),
+                    ExprKind::Value(Value::Number("1".into())).into(),
+                    ExprKind::Value(Value::Number("2".into())).into(),
                 ],
-            ],
+                vec![ExprKind::Value(Value::String("1".into())).into(), ExprKind::Default.into()],
+            ]),
+            on_conflict: None,
+            returning: vec![],
         };
-        let have = Parser::new(input).unwrap().parse_insert().unwrap();
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_insert().unwrap();
+        assert_eq!(want, have);
 
-        assert_eq!(want, have)
+        let input = "insert into s1.t1 (c1, c2) select * from t2";
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_insert().unwrap();
+        assert_eq!(Ident::Compound(vec!["s1".into(), "t1".into()]), have.table);
+        assert_eq!(vec![Name::from("c1"), Name::from("c2")], have.columns);
+        match have.source {
+            InsertSource::Select(select) => assert_eq!("SELECT * FROM t2", select.to_string()),
+            source => panic!("want select, have: {source:?}"),
+        }
+
+        let input = "insert into t1 default values";
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_insert().unwrap();
+        assert_eq!(InsertSource::DefaultValues, have.source);
     }
 
     #[test]
     fn test_parse_update() {
-        let input = "update t1 set c1 = 1, c2 = \"2\" where 1 = 1";
+        let input = "update t1 set c1 = 1, c2 = '2' where 1 = 1";
 
         let want = Update {
             table: Ident::Single("t1".into()),
             set: vec![
                 Set {
                     column: Ident::Single("c1".into()),
-                    expr: Expr::Value(Value::Number("1".into())),
+                    expr: ExprKind::Value(Value::Number("1".into())).into(),
                 },
                 Set {
                     column: Ident::Single("c2".into()),
-                    expr: Expr::Value(Value::String("2".into())),
+                    expr: ExprKind::Value(Value::String("2".into())).into(),
                 },
             ],
-            filter: Some(Expr::BinaryOp {
-                left: Box::new(Expr::Value(Value::Number("1".into()))),
-                op: Op::Eq,
-                right: Box::new(Expr::Value(Value::Number("1".into()))),
-            }),
+            filter: Some(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Value(Value::Number("1".into())).into()),
+                    op: Op::Eq,
+                    right: Box::new(ExprKind::Value(Value::Number("1".into())).into()),
+                }
+                .into(),
+            ),
+            returning: vec![],
         };
-        let have = Parser::new(input).unwrap().parse_update().unwrap();
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_update().unwrap();
 
         assert_eq!(want, have)
     }
@@ -1102,14 +3071,512 @@ mod test {
 
         let want = Delete {
             table: Ident::Single("t1".into()),
-            filter: Some(Expr::BinaryOp {
-                left: Box::new(Expr::Value(Value::Number("1".into()))),
-                op: Op::Eq,
-                right: Box::new(Expr::Value(Value::Number("1".into()))),
-            }),
+            filter: Some(
+                ExprKind::BinaryOp {
+                    left: Box::new(ExprKind::Value(Value::Number("1".into())).into()),
+                    op: Op::Eq,
+                    right: Box::new(ExprKind::Value(Value::Number("1".into())).into()),
+                }
+                .into(),
+            ),
+            returning: vec![],
+        };
+        let have = Parser::new(input, &GenericDialect).unwrap().parse_delete().unwrap();
+
+        assert_eq!(want, have);
+    }
+
+    #[test]
+    fn test_parse_upsert_and_returning() {
+        let one = || -> Expr { ExprKind::Value(Value::Number("1".into())).into() };
+        let set = |column: &str, expr| Set { column: Ident::Single(column.into()), expr };
+        let parse = |input| Parser::new(input, &GenericDialect).unwrap().parse_insert().unwrap();
+
+        let have = parse("insert into t1 values (1) on conflict do nothing");
+        let want = OnConflict::OnConflict { columns: vec![], action: ConflictAction::DoNothing };
+        assert_eq!(Some(want), have.on_conflict);
+
+        let have =
+            parse("insert into t1 values (1) on conflict (c1, c2) do update set c1 = 1 where true");
+        let want = OnConflict::OnConflict {
+            columns: vec!["c1".into(), "c2".into()],
+            action: ConflictAction::DoUpdate {
+                set: vec![set("c1", one())],
+                filter: Some(ExprKind::Value(Value::Bool(true)).into()),
+            },
+        };
+        assert_eq!(Some(want), have.on_conflict);
+
+        let have = parse("insert into t1 values (1) on duplicate key update c1 = 1, c2 = 1");
+        let want = OnConflict::DuplicateKeyUpdate(vec![set("c1", one()), set("c2", one())]);
+        assert_eq!(Some(want), have.on_conflict);
+
+        let have =
+            parse("insert into t1 default values on conflict do nothing returning *, c1 as a");
+        let want: Vec<SelectItem> = vec![
+            SelectItemKind::Wildcard.into(),
+            SelectItemKind::AliasedExpr {
+                expr: ExprKind::Ident(Ident::Single("c1".into())).into(),
+                alias: "a".into(),
+            }
+            .into(),
+        ];
+        assert_eq!(want, have.returning);
+
+        let input = "update t1 set c1 = 1 returning c1; delete from t1 where true returning *";
+        let have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        let returning = have.iter().map(|s| match &s.kind {
+            StatementKind::Update(update) => update.returning.len(),
+            StatementKind::Delete(delete) => delete.returning.len(),
+            kind => panic!("want update or delete, have: {kind:?}"),
+        });
+        assert_eq!(vec![1, 1], returning.collect::<Vec<_>>());
+
+        let have =
+            Parser::new("insert into t1 values (1) on conflict do update c1 = 1", &GenericDialect)
+                .and_then(|mut p| p.parse())
+                .unwrap_err();
+        assert_eq!("1:49: unexpected `c1`, expected `SET`", have.to_string());
+    }
+
+    #[test]
+    fn test_spans() {
+        let span = |(l1, c1, o1), (l2, c2, o2)| Span {
+            start: Location { line: l1, col: c1, offset: o1 },
+            end: Location { line: l2, col: c2, offset: o2 },
+        };
+
+        let input = "select a + 1, t1.* from t1\n  left join (select b from t2) t3 on a = b;";
+        let mut have = Parser::new(input, &GenericDialect).unwrap().parse().unwrap();
+        let statement = have.remove(0);
+        assert_eq!(span((0, 0, 0), (1, 42, 69)), statement.span);
+
+        let StatementKind::Select(select) = statement.kind else { panic!() };
+        let query = select.body;
+        assert_eq!(span((0, 7, 7), (0, 12, 12)), query.projection[0].span);
+        assert_eq!(span((0, 14, 14), (0, 18, 18)), query.projection[1].span);
+
+        let SelectItemKind::Expr(expr) = &query.projection[0].kind else { panic!() };
+        let ExprKind::BinaryOp { left, right, .. } = &expr.kind else { panic!() };
+        assert_eq!(span((0, 7, 7), (0, 12, 12)), expr.span);
+        assert_eq!(span((0, 7, 7), (0, 8, 8)), left.span);
+        assert_eq!(span((0, 11, 11), (0, 12, 12)), right.span);
+
+        assert_eq!(span((0, 24, 24), (0, 26, 26)), query.from.span);
+        assert_eq!(span((1, 2, 29), (1, 42, 69)), query.joins[0].span);
+        assert_eq!(span((1, 12, 39), (1, 33, 60)), query.joins[0].from.span);
+    }
+
+    #[test]
+    fn test_errors_have_location() {
+        let cases = [
+            ("select @ from t1", "1:8: unhandled char: @"),
+            ("select * from t1 where", "1:23: unexpected EOF, expected expression"),
+            (
+                "select * from\n t1 join t2",
+                "2:12: unexpected EOF, expected one of: `.`, `AS`, `ON`, `USING`",
+            ),
+            ("update t1 set a 1", "1:17: unexpected `1`, expected one of: `.`, `=`"),
+            ("select count(distinct) from t1", "1:22: unexpected `)`, expected expression"),
+            (
+                "selec 1",
+                "1:1: unexpected `selec`, expected one of: \
+                 `SELECT`, `INSERT`, `UPDATE`, `DELETE`, `CREATE`, `ALTER`, `DROP`, `TRUNCATE`",
+            ),
+            ("insert into t1 values (1", "1:25: unexpected EOF, expected one of: `,`, `)`"),
+            (
+                "insert into t1 (c1) value (1)",
+                "1:21: unexpected `value`, expected one of: `DEFAULT`, `VALUES`, `SELECT`",
+            ),
+        ];
+
+        for (input, want) in cases {
+            let have = Parser::new(input, &GenericDialect).and_then(|mut p| p.parse()).unwrap_err();
+            assert_eq!(want, have.to_string());
+        }
+    }
+
+    #[test]
+    fn test_error_expected() {
+        let have = Parser::new("select * from t1 where a =", &GenericDialect)
+            .and_then(|mut p| p.parse())
+            .unwrap_err();
+        let want = ParserError::Unexpected {
+            token: Token::Eof,
+            span: Span {
+                start: Location { line: 0, col: 26, offset: 26 },
+                end: Location { line: 0, col: 26, offset: 26 },
+            },
+            expected: vec![Expected::Expression],
         };
-        let have = Parser::new(input).unwrap().parse_delete().unwrap();
+        assert_eq!(want, have);
 
+        let have =
+            Parser::new("delete from 1", &GenericDialect).and_then(|mut p| p.parse()).unwrap_err();
+        let want = ParserError::Unexpected {
+            token: Token::NumberLiteral("1".into()),
+            span: Span {
+                start: Location { line: 0, col: 12, offset: 12 },
+                end: Location { line: 0, col: 13, offset: 13 },
+            },
+            expected: vec![Expected::Identifier],
+        };
         assert_eq!(want, have);
     }
+
+    #[test]
+    fn test_error_render() {
+        let input = "select *\nfrom t1\nwhere a = = 1";
+        let have = Parser::new(input, &GenericDialect).and_then(|mut p| p.parse()).unwrap_err();
+        let want = "\
+error: unexpected `=`, expected expression
+ --> 3:11
+  |
+3 | where a = = 1
+  |           ^
+";
+        assert_eq!(want, have.render(input));
+
+        let input = "select 'abc from t1";
+        let have = Parser::new(input, &GenericDialect).and_then(|mut p| p.parse()).unwrap_err();
+        let want = "\
+error: unterminated quote, want: '
+ --> 1:8
+  |
+1 | select 'abc from t1
+  |        ^
+";
+        assert_eq!(want, have.render(input));
+    }
+
+    #[test]
+    fn test_parse_with_recovery() {
+        let input = "
+            select a from t1;
+            select from t1 where;
+            insert into t1 values ((1));
+            update t1 set = 1;
+            delete from t1 where a = 1 select b from t2;
+            create t1 (a int);
+            select c from t3
+        ";
+
+        let (statements, errors) =
+            Parser::new(input, &GenericDialect).unwrap().parse_with_recovery();
+        let have: Vec<_> = statements
+            .iter()
+            .map(|s| match &s.kind {
+                StatementKind::Select(_) => "select",
+                StatementKind::Insert(_) => "insert",
+                StatementKind::Update(_) => "update",
+                StatementKind::Delete(_) => "delete",
+                StatementKind::Create(_) => "create",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(vec!["select", "insert", "delete", "select", "select"], have);
+
+        let have: Vec<_> = errors.iter().map(|e| e.span().start.line).collect();
+        assert_eq!(vec![2, 4, 6], have);
+    }
+
+    #[test]
+    fn test_parse_with_recovery_skips_inner_keywords() {
+        let input = "
+            alter table t1 drop column c1 c2, alter column c3 drop not null;
+            alter table t1
+                add column c1 int int,
+                drop column c2,
+                alter column c3 set not null
+            create table t1 (c1 int references t2 on update cascade cascade)
+            select a from t1 where
+            select b from t2";
+
+        let (_, errors) = Parser::new(input, &GenericDialect).unwrap().parse_with_recovery();
+        let have: Vec<_> = errors.iter().map(|e| e.span().start.line).collect();
+        assert_eq!(vec![1, 3, 6], have);
+    }
+
+    #[test]
+    fn test_parse_with_recovery_makes_progress() {
+        let cases = ["select", "select select select", "select (select", "a b c; select", ";;("];
+
+        for input in cases {
+            let (_, errors) = Parser::new(input, &GenericDialect).unwrap().parse_with_recovery();
+            assert!(!errors.is_empty(), "{input}");
+        }
+
+        let (statements, errors) =
+            Parser::new("select 1 from t1; select 2 from t2", &GenericDialect)
+                .unwrap()
+                .parse_with_recovery();
+        assert_eq!(2, statements.len());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        let input = format!("select {}1{} from t1", "(".repeat(1000), ")".repeat(1000));
+        let have = Parser::new(&input, &GenericDialect).unwrap().parse();
+        assert!(matches!(have, Err(ParserError::RecursionLimit { .. })));
+
+        let input =
+            format!("select * from {}t1{}", "(select * from ".repeat(1000), ")".repeat(1000));
+        let have = Parser::new(&input, &GenericDialect).unwrap().parse();
+        assert!(matches!(have, Err(ParserError::RecursionLimit { .. })));
+
+        // Chains of operators nest, so they're limited too rather than overflowing the stack when
+        // the result is displayed or dropped
+        let input = format!("select 1{} from t1", "+1".repeat(200_000));
+        let have = Parser::new(&input, &GenericDialect).unwrap().parse();
+        assert!(matches!(have, Err(ParserError::RecursionLimit { .. })));
+
+        let input = format!("select * from t1 where a = 0{}", " and a = 1 or b = 2".repeat(40));
+        let have = Parser::new(&input, &GenericDialect).unwrap().parse().unwrap();
+        let printed = have[0].to_string();
+        assert_eq!(have, Parser::new(&printed, &GenericDialect).unwrap().parse().unwrap());
+        assert!(crate::format(&input, &GenericDialect, Default::default()).is_ok());
+        drop(have);
+
+        let input = format!("select {}1{} from t1", "(".repeat(100), ")".repeat(100));
+        assert!(Parser::new(&input, &GenericDialect).unwrap().parse().is_ok());
+        let have = Parser::new(&input, &GenericDialect).unwrap().recursion_limit(50).parse();
+        assert!(matches!(have, Err(ParserError::RecursionLimit { .. })));
+    }
+
+    // Xorshift, so the property tests below are reproducible without extra dependencies
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        fn pick<'b, T>(&mut self, items: &'b [T]) -> &'b T {
+            &items[self.below(items.len())]
+        }
+    }
+
+    const SEEDS: &[&str] = &[
+        "select a, b * 2 as c from t1 join t2 on t1.id = t2.id where a between 1 and 2",
+        "select count(distinct a) filter (where b is not null) from t1 group by rollup (a, b)",
+        "select * from t1 natural left join lateral (select * from t2) t2, t3 order by a desc",
+        "select * from t1 where a not in (1, 2) or -b || 'c' = \"d\" limit 10 offset 5",
+        "insert into t1 (a, b) values (1, 'a'), (2, default); update t1 set a = 1 where b <> 2",
+        "insert into s1.t1 select * from t2; insert into t3 default values",
+        "delete from t1 where a = 1; create table t1 (c1 int, c2 varchar(255))",
+        "insert into t1 values (1) on conflict (a) do update set a = 2 where b returning *",
+        "insert into t1 values (1) on duplicate key update a = 1; delete from t1 returning a",
+        "alter table if exists t1 add column c1 int not null, drop column c2 cascade, \
+         rename c3 to c4, alter c5 set data type decimal(5, 2), rename to t2",
+        "drop table if exists t1, t2 cascade; truncate table t1; drop index i1",
+        "create unique index if not exists i1 on t1 (a desc nulls last) where b > 0",
+        "create or replace view v1 (a) as select a from t1 limit 1; create view v2 as select 1",
+        "create table t1 (a int primary key, b text[] references t2 (a) on delete cascade, \
+         constraint c check (a > 0), foreign key (b) references t3)",
+        "select $$a$$, E'\\n', `c`, /* x /* y */ */ 1 -- z\n from t1 # w",
+    ];
+
+    const FRAGMENTS: &[&str] = &[
+        "select",
+        "from",
+        "where",
+        "join",
+        "on",
+        "using",
+        "group",
+        "by",
+        "order",
+        "limit",
+        "offset",
+        "and",
+        "or",
+        "not",
+        "in",
+        "between",
+        "is",
+        "null",
+        "as",
+        "natural",
+        "cross",
+        "lateral",
+        "filter",
+        "distinct",
+        "insert",
+        "into",
+        "values",
+        "update",
+        "set",
+        "delete",
+        "create",
+        "table",
+        "int",
+        "varchar",
+        "alter",
+        "add",
+        "column",
+        "drop",
+        "rename",
+        "to",
+        "type",
+        "truncate",
+        "cascade",
+        "restrict",
+        "if",
+        "exists",
+        "index",
+        "unique",
+        "view",
+        "replace",
+        "default",
+        "conflict",
+        "do",
+        "nothing",
+        "duplicate",
+        "key",
+        "returning",
+        "primary",
+        "references",
+        "constraint",
+        "check",
+        "[",
+        "]",
+        "decimal",
+        "time",
+        "zone",
+        "(",
+        ")",
+        ",",
+        ";",
+        ".",
+        "*",
+        "+",
+        "-",
+        "/",
+        "%",
+        "||",
+        "=",
+        "!=",
+        "<",
+        "<=",
+        ">",
+        ">=",
+        "<>",
+        "t1",
+        "c1",
+        "1",
+        "2.5",
+        "1.5e3",
+        "0x1f",
+        "1e-9223372036854775808",
+        "0e-4000000000",
+        "'s'",
+        "\"q\"",
+        "`b`",
+        "$$d$$",
+        "--",
+        "/*",
+        "*/",
+        "#",
+        "@",
+        "!",
+        "|",
+        "$",
+        "é",
+        "\n",
+        " ",
+    ];
+
+    fn assert_no_panic(input: &str) {
+        for dialect in
+            [&GenericDialect as &dyn Dialect, &PostgresDialect, &MySqlDialect, &SqliteDialect]
+        {
+            for mode in [NumberMode::Text, NumberMode::Float, NumberMode::Decimal] {
+                let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    let parser = || Parser::new(input, dialect).map(|p| p.number_mode(mode));
+                    if let Ok(statements) = parser().and_then(|mut parser| parser.parse()) {
+                        statements.iter().for_each(|s| drop(s.to_string()));
+                    }
+                    let _ = parser().map(|mut parser| parser.parse_with_recovery());
+                }));
+                assert!(res.is_ok(), "{dialect:?} {mode:?} panicked on input: {input:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_never_panics_on_random_chars() {
+        let mut rng = Rng(0x5eed);
+        let chars: Vec<char> = SEEDS.concat().chars().chain("@?^~[]{}\\\0\t€".chars()).collect();
+
+        for _ in 0..2000 {
+            let len = rng.below(64);
+            let input: String = (0..len).map(|_| *rng.pick(&chars)).collect();
+            assert_no_panic(&input);
+        }
+    }
+
+    #[test]
+    fn test_parse_never_panics_on_random_tokens() {
+        let mut rng = Rng(0xdecaf);
+
+        for _ in 0..2000 {
+            let len = rng.below(32);
+            let input: Vec<&str> = (0..len).map(|_| *rng.pick(FRAGMENTS)).collect();
+            assert_no_panic(&input.join(" "));
+        }
+    }
+
+    #[test]
+    fn test_parse_never_panics_on_mutated_statements() {
+        let mut rng = Rng(0xc0ffee);
+
+        for _ in 0..2000 {
+            let mut input: Vec<&str> = rng.pick(SEEDS).split(' ').collect();
+            for _ in 0..=rng.below(3) {
+                let (i, j) = (rng.below(input.len()), rng.below(input.len()));
+                match rng.below(3) {
+                    0 => drop(input.remove(i)),
+                    1 => input.insert(i, *rng.pick(FRAGMENTS)),
+                    _ => input.swap(i, j),
+                }
+
+                if input.is_empty() {
+                    break;
+                }
+            }
+            assert_no_panic(&input.join(" "));
+        }
+    }
+
+    #[test]
+    fn test_display_round_trips_mutated_statements() {
+        let mut rng = Rng(0xbeef);
+
+        for _ in 0..2000 {
+            let mut input: Vec<&str> = rng.pick(SEEDS).split(' ').collect();
+            let (i, j) = (rng.below(input.len()), rng.below(input.len()));
+            match rng.below(3) {
+                0 => drop(input.remove(i)),
+                1 => input.insert(i, *rng.pick(FRAGMENTS)),
+                _ => input.swap(i, j),
+            }
+
+            let input = input.join(" ");
+            let Ok(want) = Parser::new(&input, &GenericDialect).and_then(|mut p| p.parse()) else {
+                continue;
+            };
+            let printed: Vec<_> = want.iter().map(|s| s.to_string()).collect();
+            let printed = printed.join(";\n");
+            let have = Parser::new(&printed, &GenericDialect).and_then(|mut p| p.parse());
+            assert_eq!(Ok(want), have, "{input:?} printed as {printed:?}");
+        }
+    }
 }
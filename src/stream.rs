@@ -0,0 +1,323 @@
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, ErrorKind, Read},
+};
+
+use crate::{
+    ast::Statement,
+    dialect::Dialect,
+    parser::{Parser, ParserError, Result},
+    tokeniser::{Location, Span, Token, TokenWithSpan, Tokeniser, TokeniserError},
+};
+
+/// The least input to read when the buffer doesn't hold a complete statement
+const MIN_READ: usize = 8 * 1024;
+
+/// How near the end of the buffer a tokeniser error can be and still be caused by a token cut off
+/// there, enough for the longest escape sequence, a surrogate pair `\uXXXX\uXXXX`
+const CUT_OFF: usize = 12;
+
+/// Parses statements one at a time from a reader, so only the statement being parsed is held in
+/// memory rather than the whole input.
+///
+/// Input is split into statements at each `;`, and spans are relative to the start of the
+/// stream. A parse error is returned in place of the statement it occurred in and the stream
+/// carries on from the next `;`, but tokeniser and IO errors end the stream. An IO error is
+/// returned after the complete statements read before it.
+pub struct StatementStream<'a, R> {
+    reader: R,
+    dialect: &'a dyn Dialect,
+    buf: String,
+    // Bytes read that don't yet make up a complete UTF-8 character
+    partial: Vec<u8>,
+    // The start of the unparsed input in `buf`, and its location in the stream
+    start: usize,
+    location: Location,
+    statements: VecDeque<Statement>,
+    // An IO error to return once the statements buffered before it have been parsed
+    error: Option<ParserError>,
+    eof: bool,
+    done: bool,
+}
+
+impl<'a, R: BufRead> StatementStream<'a, R> {
+    pub fn new(reader: R, dialect: &'a dyn Dialect) -> Self {
+        Self {
+            reader,
+            dialect,
+            buf: String::new(),
+            partial: Vec::new(),
+            start: 0,
+            location: Location::default(),
+            statements: VecDeque::new(),
+            error: None,
+            eof: false,
+            done: false,
+        }
+    }
+
+    // Tokenises up to and including the next `;`, or to the end of the input once it's all been
+    // read. Returns None if more input is needed
+    fn next_tokens(&self) -> std::result::Result<Option<Vec<TokenWithSpan<'_>>>, TokeniserError> {
+        let mut tokeniser =
+            Tokeniser::new(&self.buf[self.start..], self.dialect).starting_at(self.location);
+
+        let mut tokens = Vec::new();
+        loop {
+            match tokeniser.next_with_span() {
+                Ok(TokenWithSpan(Token::Semicolon, span)) => {
+                    tokens.push(TokenWithSpan(Token::Semicolon, span));
+                    let end = Span { start: span.end, end: span.end };
+                    tokens.push(TokenWithSpan(Token::Eof, end));
+                    return Ok(Some(tokens));
+                }
+                Ok(TokenWithSpan(Token::Eof, span)) => {
+                    tokens.push(TokenWithSpan(Token::Eof, span));
+                    return Ok(self.eof.then_some(tokens));
+                }
+                Ok(token) => tokens.push(token),
+                // The rest of the quote, comment or token may not have been read yet
+                Err(e) if !self.eof && self.cut_off(&e) => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // Whether the error could be from input cut off at the end of the buffer, rather than an
+    // error in the input itself
+    fn cut_off(&self, e: &TokeniserError) -> bool {
+        let end = self.location.offset + self.buf.len() - self.start;
+        match e {
+            TokeniserError::Unterminated { .. } | TokeniserError::UnterminatedComment { .. } => {
+                true
+            }
+            e => e.location().offset + CUT_OFF >= end,
+        }
+    }
+
+    // Reads at least as much input as is already buffered, so rescanning a long statement after
+    // each read stays linear. Input is read a chunk at a time rather than a line at a time, as a
+    // dump can put many statements on one line
+    fn read(&mut self) -> io::Result<()> {
+        self.buf.drain(..self.start);
+        self.start = 0;
+
+        let (len, want) = (self.buf.len(), self.buf.len().max(MIN_READ));
+        while self.buf.len() - len < want {
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if chunk.is_empty() {
+                if !self.partial.is_empty() {
+                    return Err(invalid_utf8());
+                }
+                self.eof = true;
+                break;
+            }
+
+            let n = chunk.len();
+            self.partial.extend_from_slice(chunk);
+            self.reader.consume(n);
+
+            // Keep the valid input before an error so the statements in it can still be parsed
+            let (valid, invalid) = match std::str::from_utf8(&self.partial) {
+                Ok(valid) => (valid, false),
+                Err(e) => {
+                    let valid = std::str::from_utf8(&self.partial[..e.valid_up_to()]);
+                    (valid.unwrap_or_default(), e.error_len().is_some())
+                }
+            };
+            self.buf.push_str(valid);
+            self.partial.drain(..valid.len());
+            if invalid {
+                return Err(invalid_utf8());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+}
+
+impl<'a, R: Read> StatementStream<'a, BufReader<R>> {
+    /// Parses statements from an unbuffered reader
+    pub fn from_read(reader: R, dialect: &'a dyn Dialect) -> Self {
+        Self::new(BufReader::new(reader), dialect)
+    }
+}
+
+impl<R: BufRead> Iterator for StatementStream<'_, R> {
+    type Item = Result<Statement>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(statement) = self.statements.pop_front() {
+                return Some(Ok(statement));
+            }
+            if self.done {
+                return None;
+            }
+
+            let tokens = match self.next_tokens() {
+                Ok(Some(tokens)) => tokens,
+                Ok(None) => {
+                    if let Some(e) = self.error.take() {
+                        self.done = true;
+                        return Some(Err(e));
+                    }
+                    if let Err(e) = self.read() {
+                        self.error = Some(ParserError::Io {
+                            kind: e.kind(),
+                            message: e.to_string(),
+                            location: self.location,
+                        });
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e.into()));
+                }
+            };
+
+            // Tokens not ending in `;` run to the end of the input
+            let done = !matches!(tokens.as_slice(), [.., TokenWithSpan(Token::Semicolon, _), _]);
+            let end = tokens[tokens.len() - 1].1.end;
+            let src = &self.buf[self.start..];
+            let statements =
+                Parser::from_tokens(tokens, src, self.location.offset, self.dialect).parse();
+
+            self.start += end.offset - self.location.offset;
+            self.location = end;
+            self.done = done;
+
+            match statements {
+                Ok(statements) => self.statements.extend(statements),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufReader, Cursor};
+
+    use super::{StatementStream, MIN_READ};
+    use crate::{
+        ast::Statement,
+        dialect::{GenericDialect, PostgresDialect},
+        parser::Parser,
+        ParserError,
+    };
+
+    #[test]
+    fn test_stream_matches_parse() {
+        let mut input = String::from("create table t1 (a int, b varchar(10));\n");
+        for i in 0..2000 {
+            input.push_str(&format!("insert into t1 values ({i}, 'row\n{i}'), ({i}, 'a;b');\n"));
+            input.push_str(
+                "/* a comment; spanning\nlines */ select a, b as Type from t1 where a = 1;",
+            );
+        }
+        input.push_str("\nselect a from t1 -- no trailing semicolon");
+
+        let want = Parser::new(&input, &GenericDialect).unwrap().parse().unwrap();
+        let have: Vec<_> = StatementStream::new(Cursor::new(&input), &GenericDialect)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(want, have);
+
+        let spans =
+            |statements: &[Statement]| -> Vec<_> { statements.iter().map(|s| s.span).collect() };
+        assert_eq!(spans(&want), spans(&have));
+        assert_eq!("select a from t1", &input[have.last().unwrap().span.range()]);
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let input = "select a from t1;\nselec b from t1;\n;select c from t1";
+        let have: Vec<_> = StatementStream::from_read(input.as_bytes(), &GenericDialect)
+            .map(|s| s.map(|s| s.to_string()).map_err(|e| e.to_string()))
+            .collect();
+        let want = vec![
+            Ok("SELECT a FROM t1".into()),
+            Err("2:1: unexpected `selec`, expected one of: \
+                 `SELECT`, `INSERT`, `UPDATE`, `DELETE`, `CREATE`, `ALTER`, `DROP`, `TRUNCATE`"
+                .into()),
+            Ok("SELECT c FROM t1".into()),
+        ];
+        assert_eq!(want, have);
+
+        let input = "select a from t1; select 'abc";
+        let have: Vec<_> = StatementStream::from_read(input.as_bytes(), &GenericDialect).collect();
+        assert!(matches!(have.as_slice(), [Ok(_), Err(ParserError::Tokeniser(_))]));
+    }
+
+    #[test]
+    fn test_stream_io_error() {
+        let input = b"select a from t1;\nselect \xff from t1;";
+        let mut stream = StatementStream::new(&input[..], &GenericDialect);
+        assert_eq!("SELECT a FROM t1", stream.next().unwrap().unwrap().to_string());
+        match stream.next() {
+            Some(Err(ParserError::Io { kind, .. })) => {
+                assert_eq!(std::io::ErrorKind::InvalidData, kind)
+            }
+            have => panic!("want io error, have: {have:?}"),
+        }
+        assert!(stream.next().is_none());
+
+        // A character cut off by the end of the input
+        let input = b"select a from t1; select '\xc3";
+        let have: Vec<_> = StatementStream::new(&input[..], &GenericDialect).collect();
+        assert!(matches!(have.as_slice(), [Ok(_), Err(ParserError::Io { .. })]));
+    }
+
+    #[test]
+    fn test_stream_split_characters() {
+        // Every multi-byte character is split between reads
+        let input = "select 'é', 'ü' from t1; select '日本' from t2;".repeat(100);
+        let reader = BufReader::with_capacity(1, input.as_bytes());
+
+        let want = Parser::new(&input, &GenericDialect).unwrap().parse().unwrap();
+        let have: Vec<_> =
+            StatementStream::new(reader, &GenericDialect).collect::<Result<_, _>>().unwrap();
+        assert_eq!(want, have);
+    }
+
+    #[test]
+    fn test_stream_error_stops_reading() {
+        let mut input = String::from("select @ from t1;");
+        input.push_str(&"select a from t1;".repeat(MIN_READ));
+        let mut reader = Cursor::new(&input);
+
+        let mut stream =
+            StatementStream::new(BufReader::with_capacity(1024, &mut reader), &GenericDialect);
+        assert!(matches!(stream.next(), Some(Err(ParserError::Tokeniser(_)))));
+        assert!(stream.next().is_none());
+        drop(stream);
+        assert!(reader.position() <= 2 * MIN_READ as u64);
+    }
+
+    #[test]
+    fn test_stream_split_tokens() {
+        // Each token is split between the first read and the next
+        for token in ["a || b", "a != b", "$$a$$", "$tag$a$tag$", "E'\\uD83D\\uDE00'"] {
+            let pad = MIN_READ - "select ".len() - token.len() / 2;
+            let mut input = " ".repeat(pad);
+            input.push_str(&format!("select {token} from t1;"));
+            let reader = BufReader::with_capacity(1, input.as_bytes());
+
+            let want = Parser::new(&input, &PostgresDialect).unwrap().parse().unwrap();
+            let have: Vec<_> = StatementStream::new(reader, &PostgresDialect)
+                .collect::<Result<_, _>>()
+                .unwrap_or_else(|e| panic!("{token}: {e}"));
+            assert_eq!(want, have, "{token}");
+        }
+    }
+}
@@ -1,15 +1,20 @@
-use std::{iter::Peekable, str::Chars};
+use std::{borrow::Cow, iter::Peekable, ops::Range, str::Chars};
 
+use crate::dialect::Dialect;
+
+/// A token, borrowing its text from the source unless it had to be unescaped
 #[derive(Debug, PartialEq, Clone)]
-pub(crate) enum Token {
+pub enum Token<'a> {
     Eof,
 
     Keyword(Keyword),
-    Ident(String),
+    Ident(Cow<'a, str>),
+    Comment(Cow<'a, str>),
 
     // Literals
-    StringLiteral(String),
-    NumberLiteral(String),
+    StringLiteral(Cow<'a, str>),
+    NumberLiteral(Cow<'a, str>),
+    HexStringLiteral(Cow<'a, str>),
 
     // Operators
     Eq,
@@ -18,122 +23,293 @@ pub(crate) enum Token {
     Le,
     Gt,
     Ge,
+    Plus,
+    Minus,
+    Slash,
+    Percent,
+    Concat,
 
     // Symbols
     LParen,
     RParen,
+    LBracket,
+    RBracket,
     Semicolon,
     Comma,
     Asterisk,
     Dot,
 }
 
-#[derive(Debug, PartialEq, Default, Clone, Copy)]
-pub(crate) struct Location {
-    line: u64,
-    col: u64,
+impl Token<'_> {
+    /// Copy any borrowed text so the token can outlive the source
+    pub fn into_owned(self) -> Token<'static> {
+        let owned = |s: Cow<str>| Cow::Owned(s.into_owned());
+        match self {
+            Token::Eof => Token::Eof,
+            Token::Keyword(kw) => Token::Keyword(kw),
+            Token::Ident(s) => Token::Ident(owned(s)),
+            Token::Comment(s) => Token::Comment(owned(s)),
+            Token::StringLiteral(s) => Token::StringLiteral(owned(s)),
+            Token::NumberLiteral(s) => Token::NumberLiteral(owned(s)),
+            Token::HexStringLiteral(s) => Token::HexStringLiteral(owned(s)),
+            Token::Eq => Token::Eq,
+            Token::Neq => Token::Neq,
+            Token::Lt => Token::Lt,
+            Token::Le => Token::Le,
+            Token::Gt => Token::Gt,
+            Token::Ge => Token::Ge,
+            Token::Plus => Token::Plus,
+            Token::Minus => Token::Minus,
+            Token::Slash => Token::Slash,
+            Token::Percent => Token::Percent,
+            Token::Concat => Token::Concat,
+            Token::LParen => Token::LParen,
+            Token::RParen => Token::RParen,
+            Token::LBracket => Token::LBracket,
+            Token::RBracket => Token::RBracket,
+            Token::Semicolon => Token::Semicolon,
+            Token::Comma => Token::Comma,
+            Token::Asterisk => Token::Asterisk,
+            Token::Dot => Token::Dot,
+        }
+    }
 }
 
-impl std::fmt::Display for Location {
+impl std::fmt::Display for Token<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}:{}", self.line, self.col)
+        match self {
+            Token::Eof => write!(f, "EOF"),
+            Token::Keyword(kw) => write!(f, "{}", kw.as_str()),
+            Token::Ident(s) | Token::Comment(s) | Token::NumberLiteral(s) => write!(f, "{s}"),
+            Token::StringLiteral(s) => write!(f, "'{}'", s.replace('\'', "''")),
+            Token::HexStringLiteral(s) => write!(f, "X'{s}'"),
+            Token::Eq => write!(f, "="),
+            Token::Neq => write!(f, "!="),
+            Token::Lt => write!(f, "<"),
+            Token::Le => write!(f, "<="),
+            Token::Gt => write!(f, ">"),
+            Token::Ge => write!(f, ">="),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Slash => write!(f, "/"),
+            Token::Percent => write!(f, "%"),
+            Token::Concat => write!(f, "||"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::LBracket => write!(f, "["),
+            Token::RBracket => write!(f, "]"),
+            Token::Semicolon => write!(f, ";"),
+            Token::Comma => write!(f, ","),
+            Token::Asterisk => write!(f, "*"),
+            Token::Dot => write!(f, "."),
+        }
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub(crate) struct TokenWithLocation(pub Token, pub Location);
+/// A position in the source. `line` and `col` are zero-based, with `col` counting chars, and
+/// `offset` is the byte offset from the start of the source
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Default, Clone, Copy)]
+pub struct Location {
+    pub line: u64,
+    pub col: u64,
+    pub offset: usize,
+}
 
-#[derive(Debug, PartialEq)]
-pub(crate) struct Word {
-    value: String,
-    keyword: Keyword,
+impl Location {
+    /// The zero-based column in UTF-16 code units, as used by e.g. the Language Server Protocol.
+    /// `src` should be the source the location was produced from, None is returned if the offset
+    /// isn't a char boundary in it
+    pub fn utf16_col(&self, src: &str) -> Option<u64> {
+        let before = src.get(..self.offset)?;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Some(before[line_start..].encode_utf16().count() as u64)
+    }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub(crate) enum Keyword {
-    Int,
-    Varchar,
-
-    Create,
-    Table,
-    Select,
-    Insert,
-    Update,
-    Delete,
-    Into,
-    Values,
-    From,
-    Where,
-    Join,
-    On,
-    Using,
-    As,
-    Limit,
-    And,
-    Or,
-    Not,
-    Null,
-    In,
-    Between,
-    Is,
-    Group,
-    By,
-    Order,
-    Asc,
-    Desc,
-    Set,
-    True,
-    False,
+/// Displayed as a one-based `line:col`
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line + 1, self.col + 1)
+    }
 }
 
-impl TryFrom<String> for Keyword {
-    type Error = ();
-
-    fn try_from(s: String) -> Result<Self, Self::Error> {
-        let kw = match s.as_str() {
-            "AND" => Keyword::And,
-            "AS" => Keyword::As,
-            "ASC" => Keyword::Asc,
-            "BETWEEN" => Keyword::Between,
-            "BY" => Keyword::By,
-            "CREATE" => Keyword::Create,
-            "DELETE" => Keyword::Delete,
-            "DESC" => Keyword::Desc,
-            "FALSE" => Keyword::False,
-            "FROM" => Keyword::From,
-            "GROUP" => Keyword::Group,
-            "IN" => Keyword::In,
-            "INSERT" => Keyword::Insert,
-            "INT" => Keyword::Int,
-            "INTO" => Keyword::Into,
-            "IS" => Keyword::Is,
-            "JOIN" => Keyword::Join,
-            "LIMIT" => Keyword::Limit,
-            "NOT" => Keyword::Not,
-            "NULL" => Keyword::Null,
-            "ON" => Keyword::On,
-            "OR" => Keyword::Or,
-            "SELECT" => Keyword::Select,
-            "SET" => Keyword::Set,
-            "TABLE" => Keyword::Table,
-            "TRUE" => Keyword::True,
-            "UPDATE" => Keyword::Update,
-            "USING" => Keyword::Using,
-            "VALUES" => Keyword::Values,
-            "VARCHAR" => Keyword::Varchar,
-            "WHERE" => Keyword::Where,
-            "ORDER" => Keyword::Order,
-
-            _ => Err(())?,
-        };
+/// A range of source from `start` up to, but not including, `end`
+#[derive(Debug, PartialEq, Default, Clone, Copy)]
+pub struct Span {
+    pub start: Location,
+    pub end: Location,
+}
 
-        Ok(kw)
+impl Span {
+    /// The byte range of the span, for slicing the source with `&src[span.range()]`
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct TokenWithSpan<'a>(pub Token<'a>, pub Span);
+
+macro_rules! keywords {
+    ($($kw:ident => $s:literal,)*) => {
+        #[derive(Debug, PartialEq, Clone, Copy)]
+        pub enum Keyword {
+            $($kw,)*
+        }
+
+        impl Keyword {
+            pub(crate) const ALL: &'static [Keyword] = &[$(Keyword::$kw,)*];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Keyword::$kw => $s,)*
+                }
+            }
+        }
+
+        /// Case insensitive, uppercases into a buffer on the stack instead of allocating
+        impl TryFrom<&str> for Keyword {
+            type Error = ();
+
+            fn try_from(s: &str) -> Result<Self, Self::Error> {
+                const MAX_LEN: usize = {
+                    let mut max = 0;
+                    $(if $s.len() > max { max = $s.len(); })*
+                    max
+                };
+
+                let mut buf = [0; MAX_LEN];
+                let upper = buf.get_mut(..s.len()).ok_or(())?;
+                upper.copy_from_slice(s.as_bytes());
+                upper.make_ascii_uppercase();
+
+                let kw = match std::str::from_utf8(upper).map_err(|_| ())? {
+                    $($s => Keyword::$kw,)*
+                    _ => Err(())?,
+                };
+
+                Ok(kw)
+            }
+        }
+    };
+}
+
+keywords! {
+    Smallint => "SMALLINT",
+    Int => "INT",
+    Integer => "INTEGER",
+    Bigint => "BIGINT",
+    Boolean => "BOOLEAN",
+    Bool => "BOOL",
+    Real => "REAL",
+    Double => "DOUBLE",
+    Precision => "PRECISION",
+    Decimal => "DECIMAL",
+    Numeric => "NUMERIC",
+    Char => "CHAR",
+    Varchar => "VARCHAR",
+    Text => "TEXT",
+    Date => "DATE",
+    Time => "TIME",
+    Timestamp => "TIMESTAMP",
+    With => "WITH",
+    Without => "WITHOUT",
+    Zone => "ZONE",
+    Blob => "BLOB",
+    Bytea => "BYTEA",
+    Json => "JSON",
+    Array => "ARRAY",
+
+    Create => "CREATE",
+    Table => "TABLE",
+    Alter => "ALTER",
+    Add => "ADD",
+    Column => "COLUMN",
+    Rename => "RENAME",
+    To => "TO",
+    Data => "DATA",
+    Type => "TYPE",
+    Drop => "DROP",
+    Index => "INDEX",
+    View => "VIEW",
+    Replace => "REPLACE",
+    Truncate => "TRUNCATE",
+    Conflict => "CONFLICT",
+    Do => "DO",
+    Nothing => "NOTHING",
+    Duplicate => "DUPLICATE",
+    Returning => "RETURNING",
+    If => "IF",
+    Exists => "EXISTS",
+    Constraint => "CONSTRAINT",
+    Default => "DEFAULT",
+    Primary => "PRIMARY",
+    Key => "KEY",
+    Unique => "UNIQUE",
+    Check => "CHECK",
+    Foreign => "FOREIGN",
+    References => "REFERENCES",
+    Cascade => "CASCADE",
+    Restrict => "RESTRICT",
+    No => "NO",
+    Action => "ACTION",
+    Select => "SELECT",
+    Insert => "INSERT",
+    Update => "UPDATE",
+    Delete => "DELETE",
+    Into => "INTO",
+    Values => "VALUES",
+    From => "FROM",
+    Where => "WHERE",
+    Join => "JOIN",
+    Inner => "INNER",
+    Left => "LEFT",
+    Right => "RIGHT",
+    Full => "FULL",
+    Outer => "OUTER",
+    Cross => "CROSS",
+    Natural => "NATURAL",
+    Lateral => "LATERAL",
+    On => "ON",
+    Using => "USING",
+    As => "AS",
+    Limit => "LIMIT",
+    Offset => "OFFSET",
+    And => "AND",
+    Or => "OR",
+    Not => "NOT",
+    Null => "NULL",
+    In => "IN",
+    Between => "BETWEEN",
+    Is => "IS",
+    Group => "GROUP",
+    Grouping => "GROUPING",
+    Sets => "SETS",
+    Rollup => "ROLLUP",
+    Cube => "CUBE",
+    Having => "HAVING",
+    By => "BY",
+    Order => "ORDER",
+    Asc => "ASC",
+    Desc => "DESC",
+    Distinct => "DISTINCT",
+    Filter => "FILTER",
+    Nulls => "NULLS",
+    First => "FIRST",
+    Last => "LAST",
+    Set => "SET",
+    True => "TRUE",
+    False => "FALSE",
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum TokeniserError {
     Unexpected { want: char, have: char, location: Location },
+    Unterminated { quote: char, location: Location },
+    UnterminatedComment { location: Location },
+    Unhandled { have: char, location: Location },
+    InvalidHexDigit { have: char, location: Location },
+    InvalidEscape { location: Location },
 }
 
 impl std::fmt::Display for TokeniserError {
@@ -142,6 +318,21 @@ impl std::fmt::Display for TokeniserError {
             TokeniserError::Unexpected { want, have, location } => {
                 write!(f, "{}: unexpected char, want: {}, have: {}", location, want, have)
             }
+            TokeniserError::Unterminated { quote, location } => {
+                write!(f, "{}: unterminated quote, want: {}", location, quote)
+            }
+            TokeniserError::UnterminatedComment { location } => {
+                write!(f, "{}: unterminated block comment", location)
+            }
+            TokeniserError::Unhandled { have, location } => {
+                write!(f, "{}: unhandled char: {}", location, have)
+            }
+            TokeniserError::InvalidHexDigit { have, location } => {
+                write!(f, "{}: invalid hex digit: {}", location, have)
+            }
+            TokeniserError::InvalidEscape { location } => {
+                write!(f, "{}: invalid escape sequence", location)
+            }
         }
     }
 }
@@ -149,23 +340,75 @@ impl std::fmt::Display for TokeniserError {
 impl std::error::Error for TokeniserError {}
 
 impl TokeniserError {
+    pub fn location(&self) -> Location {
+        match self {
+            TokeniserError::Unexpected { location, .. }
+            | TokeniserError::Unterminated { location, .. }
+            | TokeniserError::UnterminatedComment { location }
+            | TokeniserError::Unhandled { location, .. }
+            | TokeniserError::InvalidHexDigit { location, .. }
+            | TokeniserError::InvalidEscape { location } => *location,
+        }
+    }
+
     fn unexpected(want: char, have: Option<char>, location: Location) -> Self {
         Self::Unexpected { want, have: have.unwrap_or(' '), location }
     }
 }
 
-pub(crate) struct Tokeniser<'a> {
+/// Controls which quote characters delimit string literals and identifiers
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum QuoteStyle {
+    /// `'...'` strings and `"..."` identifiers, with quotes escaped by doubling them
+    Ansi,
+    /// `'...'` and `"..."` strings with backslash escapes, and `` `...` `` identifiers
+    MySql,
+    /// As [`QuoteStyle::Ansi`], plus `E'...'` escape strings and `$tag$...$tag$` dollar quoting
+    Postgres,
+}
+
+pub struct Tokeniser<'a> {
+    src: &'a str,
     chars: Peekable<Chars<'a>>,
     line: u64,
     col: u64,
+    offset: usize,
+    base: Location,
+    dialect: &'a dyn Dialect,
+    quote_style: QuoteStyle,
+    preserve_comments: bool,
 }
 
 impl<'a> Tokeniser<'a> {
-    pub fn new(src: &'a str) -> Self {
-        Self { chars: src.chars().peekable(), line: 0, col: 0 }
+    pub fn new(src: &'a str, dialect: &'a dyn Dialect) -> Self {
+        let quote_style = dialect.quote_style();
+        Self {
+            src,
+            chars: src.chars().peekable(),
+            line: 0,
+            col: 0,
+            offset: 0,
+            base: Location::default(),
+            dialect,
+            quote_style,
+            preserve_comments: false,
+        }
     }
 
-    pub fn collect(mut self) -> Result<Vec<Token>, TokeniserError> {
+    /// Report locations relative to `location`, for tokenising a fragment of a larger source
+    pub fn starting_at(mut self, location: Location) -> Self {
+        self.base = location;
+        self
+    }
+
+    /// Emit comments as [`Token::Comment`] instead of skipping them
+    pub fn preserve_comments(mut self, preserve: bool) -> Self {
+        self.preserve_comments = preserve;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn collect(mut self) -> Result<Vec<Token<'a>>, TokeniserError> {
         let mut v = Vec::new();
         while {
             let token = self.next()?;
@@ -177,12 +420,12 @@ impl<'a> Tokeniser<'a> {
         Ok(v)
     }
 
-    pub fn collect_with_location(mut self) -> Result<Vec<TokenWithLocation>, TokeniserError> {
+    pub fn collect_with_span(mut self) -> Result<Vec<TokenWithSpan<'a>>, TokeniserError> {
         let mut v = Vec::new();
         while {
-            let TokenWithLocation(token, location) = self.next_with_location()?;
+            let TokenWithSpan(token, span) = self.next_with_span()?;
             let eof = token == Token::Eof;
-            v.push(TokenWithLocation(token, location));
+            v.push(TokenWithSpan(token, span));
             !eof
         } {}
 
@@ -190,58 +433,62 @@ impl<'a> Tokeniser<'a> {
     }
 
     pub fn location(&self) -> Location {
-        Location { line: self.line, col: self.col }
+        let (line, col) = match self.line {
+            0 => (self.base.line, self.base.col + self.col),
+            line => (self.base.line + line, self.col),
+        };
+
+        Location { line, col, offset: self.base.offset + self.offset }
     }
 
-    pub fn next_with_location(&mut self) -> Result<TokenWithLocation, TokeniserError> {
-        self.skip_whitespace();
-        let loc = self.location();
-        self.next().map(|t| TokenWithLocation(t, loc))
+    pub fn next_with_span(&mut self) -> Result<TokenWithSpan<'a>, TokeniserError> {
+        self.skip_whitespace()?;
+        let start = self.location();
+        let token = self.next()?;
+        Ok(TokenWithSpan(token, Span { start, end: self.location() }))
     }
 
-    pub fn next(&mut self) -> Result<Token, TokeniserError> {
-        if !self.skip_whitespace() {
+    fn next(&mut self) -> Result<Token<'a>, TokeniserError> {
+        if !self.skip_whitespace()? {
             return Ok(Token::Eof);
         }
 
+        if self.at_comment() {
+            return self.comment().map(|s| Token::Comment(s.into()));
+        }
+
         match self.peek_char() {
             Some(&c) => match c {
-                '0'..='9' | '.' => {
-                    let mut s = self.peeking_take_while(|c| c.is_numeric());
-
-                    if let Some('.') = self.peek_char() {
-                        self.next_char();
-                        s.push('.');
-                        s.push_str(&self.peeking_take_while(|c| c.is_numeric()))
-                    }
-
-                    if s == "." {
-                        return Ok(Token::Dot);
-                    }
-
-                    Ok(Token::NumberLiteral(s))
+                '0'..='9' | '.' => Ok(self.number()),
+                '\'' => {
+                    let escapes = self.quote_style == QuoteStyle::MySql;
+                    self.quoted('\'', escapes).map(Token::StringLiteral)
+                }
+                '"' if self.quote_style == QuoteStyle::MySql => {
+                    self.quoted('"', true).map(Token::StringLiteral)
                 }
-                '"' => {
+                '"' => self.quoted('"', false).map(Token::Ident),
+                '`' if self.quote_style == QuoteStyle::MySql => {
+                    self.quoted('`', false).map(Token::Ident)
+                }
+                'X' | 'x' if self.peek_nth_char(1) == Some('\'') => {
+                    let location = self.location();
                     self.next_char();
-                    let s = self.peeking_take_while(|c| c != '"');
-                    match self.next_char() {
-                        Some('"') => {}
-                        have => Err(TokeniserError::unexpected('"', have, self.location()))?,
+                    let s = self.quoted('\'', false)?;
+                    match s.chars().find(|c| !c.is_ascii_hexdigit()) {
+                        Some(have) => Err(TokeniserError::InvalidHexDigit { have, location }),
+                        None => Ok(Token::HexStringLiteral(s)),
                     }
-
-                    Ok(Token::StringLiteral(s))
                 }
-                '`' => {
+                'E' | 'e'
+                    if self.quote_style == QuoteStyle::Postgres
+                        && self.peek_nth_char(1) == Some('\'') =>
+                {
                     self.next_char();
-                    let s = self.peeking_take_while(|c| {
-                        c.is_alphabetic() || c.is_ascii_digit() || c == '_'
-                    });
-                    match self.next_char() {
-                        Some('`') => {}
-                        have => Err(TokeniserError::unexpected('`', have, self.location()))?,
-                    }
-
-                    Ok(Token::Ident(s))
+                    self.quoted('\'', true).map(Token::StringLiteral)
+                }
+                '$' if self.quote_style == QuoteStyle::Postgres => {
+                    self.dollar_quoted().map(Token::StringLiteral)
                 }
                 '>' => {
                     self.next_char();
@@ -254,88 +501,337 @@ impl<'a> Tokeniser<'a> {
                     self.next_char();
                     match self.peek_char() {
                         Some('=') => self.consume(Token::Le),
+                        Some('>') => self.consume(Token::Neq),
                         _ => Ok(Token::Lt),
                     }
                 }
                 '!' => {
                     self.next_char();
                     match self.next_char() {
-                        Some('=') => self.consume(Token::Neq),
-                        have => Err(TokeniserError::unexpected('`', have, self.location()))?,
+                        Some('=') => Ok(Token::Neq),
+                        have => Err(TokeniserError::unexpected('=', have, self.location()))?,
+                    }
+                }
+                '|' => {
+                    self.next_char();
+                    match self.next_char() {
+                        Some('|') => Ok(Token::Concat),
+                        have => Err(TokeniserError::unexpected('|', have, self.location()))?,
                     }
                 }
                 '=' => self.consume(Token::Eq),
+                '+' => self.consume(Token::Plus),
+                '-' => self.consume(Token::Minus),
+                '/' => self.consume(Token::Slash),
+                '%' => self.consume(Token::Percent),
                 '(' => self.consume(Token::LParen),
                 ')' => self.consume(Token::RParen),
+                '[' => self.consume(Token::LBracket),
+                ']' => self.consume(Token::RBracket),
                 ',' => self.consume(Token::Comma),
                 ';' => self.consume(Token::Semicolon),
                 '*' => self.consume(Token::Asterisk),
-                ch if ch.is_ascii_lowercase() || ch.is_ascii_uppercase() || ch == '_' => {
+                ch if ch.is_alphabetic() || ch == '_' => {
                     // identifier or keyword:
 
                     let s = self.peeking_take_while(|c| {
                         c.is_alphabetic() || c.is_ascii_digit() || c == '_'
                     });
 
-                    match Keyword::try_from(s.to_uppercase()) {
-                        Ok(kw) => Ok(Token::Keyword(kw)),
-                        _ => Ok(Token::Ident(s)),
+                    match Keyword::try_from(s) {
+                        Ok(kw) if self.dialect.is_keyword(kw) => Ok(Token::Keyword(kw)),
+                        _ => Ok(Token::Ident(s.into())),
                     }
                 }
-                ch => unimplemented!("unhandled char: {ch}"),
+                have => Err(TokeniserError::Unhandled { have, location: self.location() }),
             },
             None => Ok(Token::Eof),
         }
     }
 
-    fn consume(&mut self, t: Token) -> Result<Token, TokeniserError> {
+    /// Tokenise a number, e.g. `1`, `1.5`, `.5e-3`, `0xFF` or `0b101`, returning [`Token::Dot`] if
+    /// there are no digits
+    fn number(&mut self) -> Token<'a> {
+        let (src, start) = (self.src, self.offset);
+
+        let radix = match (self.peek_nth_char(0), self.peek_nth_char(1), self.peek_nth_char(2)) {
+            (Some('0'), Some('x' | 'X'), Some(c)) if c.is_ascii_hexdigit() => 16,
+            (Some('0'), Some('b' | 'B'), Some('0' | '1')) => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.next_char();
+            self.next_char();
+            self.digits(radix);
+            return Token::NumberLiteral(src[start..self.offset].into());
+        }
+
+        let mut digits = self.digits(10);
+        if let Some('.') = self.peek_char() {
+            self.next_char();
+            digits |= self.digits(10);
+        }
+        if !digits {
+            return Token::Dot;
+        }
+
+        // Only take the exponent if it's well formed, so e.g. `1e` is a number and an identifier
+        if let Some('e' | 'E') = self.peek_char() {
+            let sign = matches!(self.peek_nth_char(1), Some('+' | '-')) as usize;
+            if self.peek_nth_char(1 + sign).is_some_and(|c| c.is_ascii_digit()) {
+                for _ in 0..=sign {
+                    self.next_char();
+                }
+                self.digits(10);
+            }
+        }
+
+        Token::NumberLiteral(src[start..self.offset].into())
+    }
+
+    /// Consume a run of digits in `radix`, with `_` separators between digits if the dialect
+    /// supports them. Returns true if there were any digits
+    fn digits(&mut self, radix: u32) -> bool {
+        let start = self.offset;
+        let separators = self.dialect.supports_numeric_separators();
+        while let Some(&c) = self.peek_char() {
+            let separator = c == '_'
+                && separators
+                && self.offset > start
+                && self.peek_nth_char(1).is_some_and(|c| c.is_digit(radix));
+            if !c.is_digit(radix) && !separator {
+                break;
+            }
+            self.next_char();
+        }
+
+        self.offset > start
+    }
+
+    /// Tokenise a quoted string or identifier starting at the opening quote. A doubled quote is
+    /// an escaped quote, and backslash escapes are handled if `escapes` is set. The contents are
+    /// borrowed from the source until the first escape
+    fn quoted(&mut self, quote: char, escapes: bool) -> Result<Cow<'a, str>, TokeniserError> {
+        let location = self.location();
+        self.next_char();
+
+        let (src, start) = (self.src, self.offset);
+        let mut unescaped: Option<String> = None;
+        loop {
+            let end = self.offset;
+            match self.next_char() {
+                Some(c) if c == quote => {
+                    if self.peek_char() != Some(&quote) {
+                        return Ok(unescaped.map_or(Cow::Borrowed(&src[start..end]), Cow::Owned));
+                    }
+                    self.next_char();
+                    unescaped.get_or_insert_with(|| src[start..end].into()).push(quote);
+                }
+                Some('\\') if escapes => {
+                    let location = self.location();
+                    let location =
+                        Location { col: location.col - 1, offset: location.offset - 1, ..location };
+                    let s = unescaped.get_or_insert_with(|| src[start..end].into());
+                    match self.next_char() {
+                        Some(c) => self.unescape(c, s, location)?,
+                        None => break,
+                    }
+                }
+                Some(c) => {
+                    if let Some(s) = &mut unescaped {
+                        s.push(c);
+                    }
+                }
+                None => break,
+            }
+        }
+
+        Err(TokeniserError::Unterminated { quote, location })
+    }
+
+    // Unescapes the escape sequence at `location`, starting with `c` after the backslash
+    fn unescape(
+        &mut self,
+        c: char,
+        s: &mut String,
+        location: Location,
+    ) -> Result<(), TokeniserError> {
+        let postgres = self.quote_style == QuoteStyle::Postgres;
+        let invalid = TokeniserError::InvalidEscape { location };
+
+        // Postgres byte values, only ASCII as other bytes would need combining into characters
+        let byte = |value: u32| {
+            char::from_u32(value).filter(char::is_ascii).ok_or_else(|| invalid.clone())
+        };
+
+        match c {
+            '0'..='7' if postgres => {
+                let (value, n) = self.escape_digits(8, 2);
+                let first = c.to_digit(8).unwrap_or_default();
+                s.push(byte(first * 8u32.pow(n) + value)?);
+            }
+            'x' if postgres => match self.escape_digits(16, 2) {
+                (_, 0) => s.push('x'),
+                (value, _) => s.push(byte(value)?),
+            },
+            'u' | 'U' if postgres => {
+                let width = if c == 'u' { 4 } else { 8 };
+                let mut value = match self.escape_digits(16, width) {
+                    (value, n) if n == width => value,
+                    _ => Err(invalid.clone())?,
+                };
+
+                // A UTF-16 surrogate pair written as two escapes
+                if (0xd800..0xdc00).contains(&value)
+                    && self.peek_nth_char(0) == Some('\\')
+                    && self.peek_nth_char(1) == Some('u')
+                {
+                    self.next_char();
+                    self.next_char();
+                    match self.escape_digits(16, 4) {
+                        (low @ 0xdc00..=0xdfff, 4) => {
+                            value = 0x10000 + ((value - 0xd800) << 10) + (low - 0xdc00)
+                        }
+                        _ => Err(invalid.clone())?,
+                    }
+                }
+
+                s.push(char::from_u32(value).ok_or(invalid)?);
+            }
+            '0' => s.push('\0'),
+            'b' => s.push('\x08'),
+            'f' if postgres => s.push('\x0c'),
+            'n' => s.push('\n'),
+            'r' => s.push('\r'),
+            't' => s.push('\t'),
+            'Z' if self.quote_style == QuoteStyle::MySql => s.push('\x1a'),
+            // MySQL keeps the backslash so these can be used in LIKE patterns
+            '%' | '_' if self.quote_style == QuoteStyle::MySql => {
+                s.push('\\');
+                s.push(c);
+            }
+            c => s.push(c),
+        }
+
+        Ok(())
+    }
+
+    // Reads up to `max` digits in `radix` following an escape, returning their value and how
+    // many were read
+    fn escape_digits(&mut self, radix: u32, max: u32) -> (u32, u32) {
+        let (mut value, mut n) = (0, 0);
+        while n < max {
+            match self.peek_char().and_then(|c| c.to_digit(radix)) {
+                Some(digit) => value = value * radix + digit,
+                None => break,
+            }
+            self.next_char();
+            n += 1;
+        }
+
+        (value, n)
+    }
+
+    /// Tokenise a Postgres dollar quoted string, e.g. `$$...$$` or `$tag$...$tag$`
+    fn dollar_quoted(&mut self) -> Result<Cow<'a, str>, TokeniserError> {
+        let location = self.location();
+        let (src, start) = (self.src, self.offset);
+        self.next_char();
+
+        let tag = self.peeking_take_while(|c| c.is_alphanumeric() || c == '_');
+        match self.next_char() {
+            Some('$') if !tag.starts_with(|c: char| c.is_ascii_digit()) => {}
+            have => Err(TokeniserError::unexpected('$', have, self.location()))?,
+        }
+
+        let delimiter = &src[start..self.offset];
+        let body = self.offset;
+        while self.next_char().is_some() {
+            if src[body..self.offset].ends_with(delimiter) {
+                return Ok(Cow::Borrowed(&src[body..self.offset - delimiter.len()]));
+            }
+        }
+
+        Err(TokeniserError::Unterminated { quote: '$', location })
+    }
+
+    fn consume(&mut self, t: Token<'a>) -> Result<Token<'a>, TokeniserError> {
         self.next_char();
         Ok(t)
     }
 
-    /// Skip any whitespace chars, returns true if there are any remaining chars
-    fn skip_whitespace(&mut self) -> bool {
+    /// Skip any whitespace chars, and comments unless they're being preserved. Returns true if
+    /// there are any remaining chars
+    fn skip_whitespace(&mut self) -> Result<bool, TokeniserError> {
         loop {
-            match self.peek_char() {
+            match self.peek_char().copied() {
                 Some(c) if c.is_whitespace() => {
                     self.next_char();
                     continue;
                 }
-                Some('#') => {
-                    self.skip_line();
+                Some(_) if !self.preserve_comments && self.at_comment() => {
+                    self.comment()?;
                     continue;
                 }
-                Some(_) => return true,
-                None => return false,
+                Some(_) => return Ok(true),
+                None => return Ok(false),
             }
         }
     }
 
-    /// Skip a line, returns true if there are any remaining chars
-    fn skip_line(&mut self) -> bool {
-        loop {
-            match self.peek_char() {
-                Some(&c) => {
+    fn at_comment(&self) -> bool {
+        let mut chars = self.chars.clone();
+        match (chars.next(), chars.next()) {
+            (Some('#'), _) => self.dialect.supports_hash_comments(),
+            (Some('-'), Some('-')) | (Some('/'), Some('*')) => true,
+            _ => false,
+        }
+    }
+
+    /// Tokenise a `#` or `--` line comment, or a `/* */` block comment, returning the comment
+    /// including its delimiters. Line comments don't include the trailing newline
+    fn comment(&mut self) -> Result<&'a str, TokeniserError> {
+        let location = self.location();
+
+        if self.peek_char() != Some(&'/') {
+            return Ok(self.peeking_take_while(|c| c != '\n'));
+        }
+
+        let (src, start) = (self.src, self.offset);
+        let nested = self.dialect.supports_nested_comments();
+        let mut depth = 0;
+        while let Some(c) = self.next_char() {
+            match (c, self.peek_char()) {
+                ('/', Some('*')) if depth == 0 || nested => {
                     self.next_char();
-                    if c == '\n' {
-                        break;
+                    depth += 1;
+                }
+                ('*', Some('/')) => {
+                    self.next_char();
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(&src[start..self.offset]);
                     }
-                    continue;
                 }
-                None => return false,
+                _ => {}
             }
         }
 
-        self.peek_char().is_some()
+        Err(TokeniserError::UnterminatedComment { location })
     }
 
     fn peek_char(&mut self) -> Option<&char> {
         self.chars.peek()
     }
 
+    fn peek_nth_char(&self, n: usize) -> Option<char> {
+        self.chars.clone().nth(n)
+    }
+
     fn next_char(&mut self) -> Option<char> {
         match self.chars.next() {
             Some(c) => {
+                self.offset += c.len_utf8();
                 if c == '\n' {
                     self.col = 0;
                     self.line += 1;
@@ -349,42 +845,45 @@ impl<'a> Tokeniser<'a> {
         }
     }
 
-    fn peeking_take_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> String {
-        let mut s = String::new();
+    fn peeking_take_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> &'a str {
+        let start = self.offset;
         while let Some(&c) = self.peek_char() {
             if predicate(c) {
                 self.next_char();
-                s.push(c);
             } else {
                 break;
             }
         }
 
-        s
+        &self.src[start..self.offset]
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::dialect::{GenericDialect, MySqlDialect, PostgresDialect};
 
     macro_rules! test_tokeniser {
         ($name:tt, $input:expr, $want:expr) => {
+            test_tokeniser!($name, &GenericDialect, $input, $want);
+        };
+        ($name:tt, $dialect:expr, $input:expr, $want:expr) => {
             #[test]
             fn $name() {
-                let tokeniser = Tokeniser::new($input);
+                let tokeniser = Tokeniser::new($input, $dialect);
                 let have = tokeniser.collect().unwrap();
                 assert_eq!(Vec::from($want), have);
             }
         };
     }
 
-    macro_rules! test_tokeniser_with_location {
+    macro_rules! test_tokeniser_with_span {
         ($name:tt, $input:expr, $want:expr) => {
             #[test]
             fn $name() {
-                let tokeniser = Tokeniser::new($input);
-                let have = tokeniser.collect_with_location().unwrap();
+                let tokeniser = Tokeniser::new($input, &GenericDialect);
+                let have = tokeniser.collect_with_span().unwrap();
                 assert_eq!(Vec::from($want), have);
             }
         };
@@ -392,12 +891,19 @@ mod test {
 
     test_tokeniser!(test_select, "SELECT", [Token::Keyword(Keyword::Select), Token::Eof]);
 
-    test_tokeniser_with_location!(
-        test_select_with_location,
+    fn span((line, col, offset): (u64, u64, usize), end: (u64, u64, usize)) -> Span {
+        Span {
+            start: Location { line, col, offset },
+            end: Location { line: end.0, col: end.1, offset: end.2 },
+        }
+    }
+
+    test_tokeniser_with_span!(
+        test_select_with_span,
         "SELECT",
         [
-            TokenWithLocation(Token::Keyword(Keyword::Select), Location { line: 0, col: 0 }),
-            TokenWithLocation(Token::Eof, Location { line: 0, col: 6 })
+            TokenWithSpan(Token::Keyword(Keyword::Select), span((0, 0, 0), (0, 6, 6))),
+            TokenWithSpan(Token::Eof, span((0, 6, 6), (0, 6, 6)))
         ]
     );
 
@@ -407,13 +913,13 @@ mod test {
         [Token::Keyword(Keyword::Select), Token::Ident("c1".into()), Token::Eof]
     );
 
-    test_tokeniser_with_location!(
-        test_whitespace_with_location,
+    test_tokeniser_with_span!(
+        test_whitespace_with_span,
         "    # This is a comment\n\tSELECT #c2\n#This is another comment\nc1",
         [
-            TokenWithLocation(Token::Keyword(Keyword::Select), Location { line: 1, col: 1 }),
-            TokenWithLocation(Token::Ident("c1".into()), Location { line: 3, col: 0 }),
-            TokenWithLocation(Token::Eof, Location { line: 3, col: 2 })
+            TokenWithSpan(Token::Keyword(Keyword::Select), span((1, 1, 25), (1, 7, 31))),
+            TokenWithSpan(Token::Ident("c1".into()), span((3, 0, 61), (3, 2, 63))),
+            TokenWithSpan(Token::Eof, span((3, 2, 63), (3, 2, 63)))
         ]
     );
 
@@ -493,22 +999,65 @@ mod test {
         ]
     );
 
+    test_tokeniser!(
+        test_operators,
+        "a + b - c * d / e % f || g != h <> i",
+        [
+            Token::Ident("a".into()),
+            Token::Plus,
+            Token::Ident("b".into()),
+            Token::Minus,
+            Token::Ident("c".into()),
+            Token::Asterisk,
+            Token::Ident("d".into()),
+            Token::Slash,
+            Token::Ident("e".into()),
+            Token::Percent,
+            Token::Ident("f".into()),
+            Token::Concat,
+            Token::Ident("g".into()),
+            Token::Neq,
+            Token::Ident("h".into()),
+            Token::Neq,
+            Token::Ident("i".into()),
+            Token::Eof
+        ]
+    );
+
     test_tokeniser!(
         test_select_string,
+        &MySqlDialect,
         "SELECT \"c1\"",
         [Token::Keyword(Keyword::Select), Token::StringLiteral("c1".into()), Token::Eof]
     );
 
     test_tokeniser!(
         test_select_multi_line_string,
+        &MySqlDialect,
         "SELECT \"c1
 2
 3\"",
         [Token::Keyword(Keyword::Select), Token::StringLiteral("c1\n2\n3".into()), Token::Eof]
     );
 
+    test_tokeniser!(
+        test_dialect_keywords,
+        &MySqlDialect,
+        "SELECT filter, nulls FROM t1",
+        [
+            Token::Keyword(Keyword::Select),
+            Token::Ident("filter".into()),
+            Token::Comma,
+            Token::Ident("nulls".into()),
+            Token::Keyword(Keyword::From),
+            Token::Ident("t1".into()),
+            Token::Eof
+        ]
+    );
+
     test_tokeniser!(
         test_select_quoted_ident,
+        &MySqlDialect,
         "SELECT `s1`.`t1`",
         [
             Token::Keyword(Keyword::Select),
@@ -518,4 +1067,253 @@ mod test {
             Token::Eof
         ]
     );
+
+    test_tokeniser!(
+        test_mysql_quoting,
+        &MySqlDialect,
+        r#"SELECT 'it''s', "a \"b\"", 'c\n\%', `my ``col```"#,
+        [
+            Token::Keyword(Keyword::Select),
+            Token::StringLiteral("it's".into()),
+            Token::Comma,
+            Token::StringLiteral("a \"b\"".into()),
+            Token::Comma,
+            Token::StringLiteral("c\n\\%".into()),
+            Token::Comma,
+            Token::Ident("my `col`".into()),
+            Token::Eof
+        ]
+    );
+
+    test_tokeniser!(
+        test_ansi_quoting,
+        &GenericDialect,
+        r#"SELECT 'it''s', 'a\n', "my ""col""", "select""#,
+        [
+            Token::Keyword(Keyword::Select),
+            Token::StringLiteral("it's".into()),
+            Token::Comma,
+            Token::StringLiteral("a\\n".into()),
+            Token::Comma,
+            Token::Ident("my \"col\"".into()),
+            Token::Comma,
+            Token::Ident("select".into()),
+            Token::Eof
+        ]
+    );
+
+    test_tokeniser!(
+        test_postgres_quoting,
+        &PostgresDialect,
+        r#"SELECT E'a\'b\n', e, $$it's$$, $fn$ $$ $fn$, "c1""#,
+        [
+            Token::Keyword(Keyword::Select),
+            Token::StringLiteral("a'b\n".into()),
+            Token::Comma,
+            Token::Ident("e".into()),
+            Token::Comma,
+            Token::StringLiteral("it's".into()),
+            Token::Comma,
+            Token::StringLiteral(" $$ ".into()),
+            Token::Comma,
+            Token::Ident("c1".into()),
+            Token::Eof
+        ]
+    );
+
+    test_tokeniser!(
+        test_postgres_escapes,
+        &PostgresDialect,
+        r"E'\x41\x4a\xg\101\0615é\U0001F600\uD83D\uDE00'",
+        [Token::StringLiteral("AJxgA15é😀😀".into()), Token::Eof]
+    );
+
+    #[test]
+    fn test_invalid_postgres_escapes() {
+        let cases = [
+            (r"E'\xff'", 2),
+            (r"E'\400'", 2),
+            (r"E'ab\u12'", 4),
+            (r"E'\U00110000'", 2),
+            (r"E'\uD83D'", 2),
+            (r"E'\uD83DA'", 2),
+        ];
+
+        for (input, col) in cases {
+            let have = Tokeniser::new(input, &PostgresDialect).collect();
+            let location = Location { line: 0, col, offset: col as usize };
+            assert_eq!(Err(TokeniserError::InvalidEscape { location }), have, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_unterminated_quote() {
+        let err = Tokeniser::new("SELECT\n  'abc", &GenericDialect).collect();
+        match err {
+            Err(TokeniserError::Unterminated { quote: '\'', location }) => {
+                assert_eq!(Location { line: 1, col: 2, offset: 9 }, location)
+            }
+            _ => panic!("want unterminated quote error, have: {err:?}"),
+        }
+    }
+
+    test_tokeniser!(
+        test_comments,
+        "SELECT -- c1\n/* c2\n*/ c1 /* c3 /* c4 */ - 1",
+        [
+            Token::Keyword(Keyword::Select),
+            Token::Ident("c1".into()),
+            Token::Minus,
+            Token::NumberLiteral("1".into()),
+            Token::Eof
+        ]
+    );
+
+    test_tokeniser!(
+        test_nested_comments,
+        &PostgresDialect,
+        "SELECT /* c1 /* c2 */ still a comment */ c1",
+        [Token::Keyword(Keyword::Select), Token::Ident("c1".into()), Token::Eof]
+    );
+
+    #[test]
+    fn test_preserve_comments() {
+        let input = "# c1\nSELECT c1 -- c2\n/* c3\n*/";
+
+        let want = [
+            TokenWithSpan(Token::Comment("# c1".into()), span((0, 0, 0), (0, 4, 4))),
+            TokenWithSpan(Token::Keyword(Keyword::Select), span((1, 0, 5), (1, 6, 11))),
+            TokenWithSpan(Token::Ident("c1".into()), span((1, 7, 12), (1, 9, 14))),
+            TokenWithSpan(Token::Comment("-- c2".into()), span((1, 10, 15), (1, 15, 20))),
+            TokenWithSpan(Token::Comment("/* c3\n*/".into()), span((2, 0, 21), (3, 2, 29))),
+            TokenWithSpan(Token::Eof, span((3, 2, 29), (3, 2, 29))),
+        ];
+        let tokeniser = Tokeniser::new(input, &GenericDialect).preserve_comments(true);
+        let have = tokeniser.collect_with_span().unwrap();
+        assert_eq!(Vec::from(want), have);
+    }
+
+    #[test]
+    fn test_multibyte_locations() {
+        let input = "SELECT 'é😀',\n  \"ü\"";
+
+        let want = [
+            TokenWithSpan(Token::Keyword(Keyword::Select), span((0, 0, 0), (0, 6, 6))),
+            TokenWithSpan(Token::StringLiteral("é😀".into()), span((0, 7, 7), (0, 11, 15))),
+            TokenWithSpan(Token::Comma, span((0, 11, 15), (0, 12, 16))),
+            TokenWithSpan(Token::Ident("ü".into()), span((1, 2, 19), (1, 5, 23))),
+            TokenWithSpan(Token::Eof, span((1, 5, 23), (1, 5, 23))),
+        ];
+        let have = Tokeniser::new(input, &GenericDialect).collect_with_span().unwrap();
+        assert_eq!(Vec::from(want), have);
+
+        assert_eq!("'é😀'", &input[have[1].1.range()]);
+        assert_eq!("\"ü\"", &input[have[3].1.range()]);
+
+        // The emoji is a surrogate pair in UTF-16
+        assert_eq!(Some(12), have[1].1.end.utf16_col(input));
+        assert_eq!(Some(2), have[3].1.start.utf16_col(input));
+
+        // Offsets past the end of, or inside a char of, some other source
+        assert_eq!(None, have[4].1.end.utf16_col("SELECT"));
+        assert_eq!(None, Location { line: 0, col: 8, offset: 8 }.utf16_col("SELECT é"));
+        assert_eq!("1:12", have[1].1.end.to_string());
+    }
+
+    #[test]
+    fn test_tokens_borrow_source() {
+        let input = "select c1, \"c 2\", 'a', 'it''s', 1.5 -- c3";
+        let tokens = Tokeniser::new(input, &GenericDialect).preserve_comments(true).collect();
+
+        let borrowed: Vec<_> = tokens
+            .unwrap()
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Ident(s)
+                | Token::StringLiteral(s)
+                | Token::NumberLiteral(s)
+                | Token::Comment(s) => Some(matches!(s, Cow::Borrowed(_))),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec![true, true, true, false, true, true], borrowed);
+    }
+
+    #[test]
+    fn test_keyword_lookup() {
+        assert_eq!(Ok(Keyword::Select), Keyword::try_from("sElEcT"));
+        assert_eq!(Ok(Keyword::Varchar), Keyword::try_from("varchar"));
+        assert_eq!(Err(()), Keyword::try_from("selects"));
+        assert_eq!(Err(()), Keyword::try_from("a_very_long_identifier"));
+        assert_eq!(Err(()), Keyword::try_from("séléct"));
+    }
+
+    test_tokeniser!(
+        test_numbers,
+        "1e-3 .5E+2 1.e2 0xFF 0b101 1_000 1e 0x 1._5",
+        [
+            Token::NumberLiteral("1e-3".into()),
+            Token::NumberLiteral(".5E+2".into()),
+            Token::NumberLiteral("1.e2".into()),
+            Token::NumberLiteral("0xFF".into()),
+            Token::NumberLiteral("0b101".into()),
+            Token::NumberLiteral("1_000".into()),
+            Token::NumberLiteral("1".into()),
+            Token::Ident("e".into()),
+            Token::NumberLiteral("0".into()),
+            Token::Ident("x".into()),
+            Token::NumberLiteral("1.".into()),
+            Token::Ident("_5".into()),
+            Token::Eof
+        ]
+    );
+
+    test_tokeniser!(
+        test_numeric_separators_unsupported,
+        &MySqlDialect,
+        "1_000",
+        [Token::NumberLiteral("1".into()), Token::Ident("_000".into()), Token::Eof]
+    );
+
+    test_tokeniser!(
+        test_hex_strings,
+        "X'0aFF' x'' x",
+        [
+            Token::HexStringLiteral("0aFF".into()),
+            Token::HexStringLiteral("".into()),
+            Token::Ident("x".into()),
+            Token::Eof
+        ]
+    );
+
+    #[test]
+    fn test_non_ascii_digits() {
+        let err = Tokeniser::new("select ٣", &GenericDialect).collect();
+        match err {
+            Err(TokeniserError::Unhandled { have: '٣', .. }) => {}
+            _ => panic!("want unhandled error, have: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_hex_digit() {
+        let err = Tokeniser::new("select X'0g'", &GenericDialect).collect();
+        match err {
+            Err(TokeniserError::InvalidHexDigit { have: 'g', location }) => {
+                assert_eq!(Location { line: 0, col: 7, offset: 7 }, location)
+            }
+            _ => panic!("want invalid hex digit error, have: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn test_unterminated_comment() {
+        let err = Tokeniser::new("SELECT /* /* */", &PostgresDialect).collect();
+        match err {
+            Err(TokeniserError::UnterminatedComment { location }) => {
+                assert_eq!(Location { line: 0, col: 7, offset: 7 }, location)
+            }
+            _ => panic!("want unterminated comment error, have: {err:?}"),
+        }
+    }
 }
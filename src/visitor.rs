@@ -0,0 +1,698 @@
+use crate::ast::{
+    AlterColumnOperation, AlterTable, AlterTableOperation, ColumnConstraintKind, ColumnDef,
+    ConflictAction, Create, CreateIndex, CreateView, Delete, Drop, Expr, ExprKind, FromTable,
+    FromTableKind, Ident, Insert, InsertSource, Join, JoinConstraint, ObjectType, OnConflict,
+    OrderByExpr, Query, Select, SelectItem, SelectItemKind, Set, Statement, StatementKind,
+    TableConstraint, TableConstraintKind, Truncate, Update,
+};
+
+/// Hooks called while walking the AST with [`Visit::visit`]. Every hook does nothing by default,
+/// `pre_` hooks are called before a node's children are walked and `post_` hooks after.
+#[allow(unused_variables)]
+pub trait Visitor {
+    fn pre_visit_statement(&mut self, statement: &Statement) {}
+    fn post_visit_statement(&mut self, statement: &Statement) {}
+
+    fn pre_visit_query(&mut self, query: &Query) {}
+    fn post_visit_query(&mut self, query: &Query) {}
+
+    fn pre_visit_select_item(&mut self, item: &SelectItem) {}
+    fn post_visit_select_item(&mut self, item: &SelectItem) {}
+
+    fn pre_visit_from_table(&mut self, from: &FromTable) {}
+    fn post_visit_from_table(&mut self, from: &FromTable) {}
+
+    fn pre_visit_join(&mut self, join: &Join) {}
+    fn post_visit_join(&mut self, join: &Join) {}
+
+    /// The name of a table or view, wherever one is read from, written to, created, altered,
+    /// dropped or referenced by a foreign key
+    fn pre_visit_table_name(&mut self, name: &Ident) {}
+    fn post_visit_table_name(&mut self, name: &Ident) {}
+
+    /// A `column = expr` of an UPDATE or upsert
+    fn pre_visit_assignment(&mut self, set: &Set) {}
+    fn post_visit_assignment(&mut self, set: &Set) {}
+
+    fn pre_visit_expr(&mut self, expr: &Expr) {}
+    fn post_visit_expr(&mut self, expr: &Expr) {}
+}
+
+/// Hooks called while walking the AST with [`VisitMut::visit_mut`], nodes can be rewritten in
+/// place. Changes made in a `pre_` hook are seen when walking the node's children.
+#[allow(unused_variables)]
+pub trait VisitorMut {
+    fn pre_visit_statement(&mut self, statement: &mut Statement) {}
+    fn post_visit_statement(&mut self, statement: &mut Statement) {}
+
+    fn pre_visit_query(&mut self, query: &mut Query) {}
+    fn post_visit_query(&mut self, query: &mut Query) {}
+
+    fn pre_visit_select_item(&mut self, item: &mut SelectItem) {}
+    fn post_visit_select_item(&mut self, item: &mut SelectItem) {}
+
+    fn pre_visit_from_table(&mut self, from: &mut FromTable) {}
+    fn post_visit_from_table(&mut self, from: &mut FromTable) {}
+
+    fn pre_visit_join(&mut self, join: &mut Join) {}
+    fn post_visit_join(&mut self, join: &mut Join) {}
+
+    /// The name of a table or view, wherever one is read from, written to, created, altered,
+    /// dropped or referenced by a foreign key
+    fn pre_visit_table_name(&mut self, name: &mut Ident) {}
+    fn post_visit_table_name(&mut self, name: &mut Ident) {}
+
+    /// A `column = expr` of an UPDATE or upsert
+    fn pre_visit_assignment(&mut self, set: &mut Set) {}
+    fn post_visit_assignment(&mut self, set: &mut Set) {}
+
+    fn pre_visit_expr(&mut self, expr: &mut Expr) {}
+    fn post_visit_expr(&mut self, expr: &mut Expr) {}
+}
+
+/// An AST node that can be walked by a [`Visitor`]
+pub trait Visit {
+    fn visit<V: Visitor>(&self, visitor: &mut V);
+}
+
+/// An AST node that can be walked by a [`VisitorMut`]
+pub trait VisitMut {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V);
+}
+
+impl<T: Visit> Visit for Vec<T> {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        for node in self {
+            node.visit(visitor);
+        }
+    }
+}
+
+impl<T: VisitMut> VisitMut for Vec<T> {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        for node in self {
+            node.visit_mut(visitor);
+        }
+    }
+}
+
+impl<T: Visit> Visit for Option<T> {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        if let Some(node) = self {
+            node.visit(visitor);
+        }
+    }
+}
+
+impl<T: VisitMut> VisitMut for Option<T> {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        if let Some(node) = self {
+            node.visit_mut(visitor);
+        }
+    }
+}
+
+impl<T: Visit> Visit for Box<T> {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        (**self).visit(visitor);
+    }
+}
+
+impl<T: VisitMut> VisitMut for Box<T> {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        (**self).visit_mut(visitor);
+    }
+}
+
+impl Visit for Statement {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        visitor.pre_visit_statement(self);
+        match &self.kind {
+            StatementKind::Select(select) => select.visit(visitor),
+            StatementKind::Insert(Insert { table, source, on_conflict, returning, .. }) => {
+                visit_table_name(table, visitor);
+                match source {
+                    InsertSource::Values(rows) => rows.visit(visitor),
+                    InsertSource::Select(select) => select.visit(visitor),
+                    InsertSource::DefaultValues => {}
+                }
+                on_conflict.visit(visitor);
+                returning.visit(visitor);
+            }
+            StatementKind::Update(Update { table, set, filter, returning }) => {
+                visit_table_name(table, visitor);
+                set.visit(visitor);
+                filter.visit(visitor);
+                returning.visit(visitor);
+            }
+            StatementKind::Delete(Delete { table, filter, returning }) => {
+                visit_table_name(table, visitor);
+                filter.visit(visitor);
+                returning.visit(visitor);
+            }
+            StatementKind::Create(Create { name, columns, constraints, .. }) => {
+                visit_table_name(name, visitor);
+                columns.visit(visitor);
+                constraints.visit(visitor);
+            }
+            StatementKind::CreateIndex(CreateIndex { table, columns, filter, .. }) => {
+                visit_table_name(table, visitor);
+                for OrderByExpr { expr, .. } in columns {
+                    expr.visit(visitor);
+                }
+                filter.visit(visitor);
+            }
+            StatementKind::CreateView(CreateView { name, query, .. }) => {
+                visit_table_name(name, visitor);
+                query.visit(visitor);
+            }
+            StatementKind::AlterTable(AlterTable { name, operations, .. }) => {
+                visit_table_name(name, visitor);
+                for operation in operations {
+                    match operation {
+                        AlterTableOperation::AddColumn { column, .. } => column.visit(visitor),
+                        AlterTableOperation::AddConstraint(constraint) => constraint.visit(visitor),
+                        AlterTableOperation::AlterColumn {
+                            op: AlterColumnOperation::SetDefault(expr),
+                            ..
+                        } => expr.visit(visitor),
+                        AlterTableOperation::RenameTo(name) => visit_table_name(name, visitor),
+                        _ => {}
+                    }
+                }
+            }
+            StatementKind::Drop(Drop { object: ObjectType::Index, .. }) => {}
+            StatementKind::Drop(Drop { names: tables, .. })
+            | StatementKind::Truncate(Truncate { tables, .. }) => {
+                for table in tables {
+                    visit_table_name(table, visitor);
+                }
+            }
+        }
+        visitor.post_visit_statement(self);
+    }
+}
+
+impl VisitMut for Statement {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        visitor.pre_visit_statement(self);
+        match &mut self.kind {
+            StatementKind::Select(select) => select.visit_mut(visitor),
+            StatementKind::Insert(Insert { table, source, on_conflict, returning, .. }) => {
+                visit_table_name_mut(table, visitor);
+                match source {
+                    InsertSource::Values(rows) => rows.visit_mut(visitor),
+                    InsertSource::Select(select) => select.visit_mut(visitor),
+                    InsertSource::DefaultValues => {}
+                }
+                on_conflict.visit_mut(visitor);
+                returning.visit_mut(visitor);
+            }
+            StatementKind::Update(Update { table, set, filter, returning }) => {
+                visit_table_name_mut(table, visitor);
+                set.visit_mut(visitor);
+                filter.visit_mut(visitor);
+                returning.visit_mut(visitor);
+            }
+            StatementKind::Delete(Delete { table, filter, returning }) => {
+                visit_table_name_mut(table, visitor);
+                filter.visit_mut(visitor);
+                returning.visit_mut(visitor);
+            }
+            StatementKind::Create(Create { name, columns, constraints, .. }) => {
+                visit_table_name_mut(name, visitor);
+                columns.visit_mut(visitor);
+                constraints.visit_mut(visitor);
+            }
+            StatementKind::CreateIndex(CreateIndex { table, columns, filter, .. }) => {
+                visit_table_name_mut(table, visitor);
+                for OrderByExpr { expr, .. } in columns {
+                    expr.visit_mut(visitor);
+                }
+                filter.visit_mut(visitor);
+            }
+            StatementKind::CreateView(CreateView { name, query, .. }) => {
+                visit_table_name_mut(name, visitor);
+                query.visit_mut(visitor);
+            }
+            StatementKind::AlterTable(AlterTable { name, operations, .. }) => {
+                visit_table_name_mut(name, visitor);
+                for operation in operations {
+                    match operation {
+                        AlterTableOperation::AddColumn { column, .. } => column.visit_mut(visitor),
+                        AlterTableOperation::AddConstraint(constraint) => {
+                            constraint.visit_mut(visitor)
+                        }
+                        AlterTableOperation::AlterColumn {
+                            op: AlterColumnOperation::SetDefault(expr),
+                            ..
+                        } => expr.visit_mut(visitor),
+                        AlterTableOperation::RenameTo(name) => visit_table_name_mut(name, visitor),
+                        _ => {}
+                    }
+                }
+            }
+            StatementKind::Drop(Drop { object: ObjectType::Index, .. }) => {}
+            StatementKind::Drop(Drop { names: tables, .. })
+            | StatementKind::Truncate(Truncate { tables, .. }) => {
+                for table in tables {
+                    visit_table_name_mut(table, visitor);
+                }
+            }
+        }
+        visitor.post_visit_statement(self);
+    }
+}
+
+// Table names have no children, so their hooks are called back to back
+fn visit_table_name<V: Visitor>(name: &Ident, visitor: &mut V) {
+    visitor.pre_visit_table_name(name);
+    visitor.post_visit_table_name(name);
+}
+
+fn visit_table_name_mut<V: VisitorMut>(name: &mut Ident, visitor: &mut V) {
+    visitor.pre_visit_table_name(name);
+    visitor.post_visit_table_name(name);
+}
+
+// Columns and constraints have no hooks of their own, only their DEFAULT and CHECK expressions
+// and REFERENCES tables are walked
+impl Visit for ColumnDef {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        for constraint in &self.constraints {
+            match &constraint.kind {
+                ColumnConstraintKind::Default(expr) | ColumnConstraintKind::Check(expr) => {
+                    expr.visit(visitor)
+                }
+                ColumnConstraintKind::References(references) => {
+                    visit_table_name(&references.table, visitor)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl VisitMut for ColumnDef {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        for constraint in &mut self.constraints {
+            match &mut constraint.kind {
+                ColumnConstraintKind::Default(expr) | ColumnConstraintKind::Check(expr) => {
+                    expr.visit_mut(visitor)
+                }
+                ColumnConstraintKind::References(references) => {
+                    visit_table_name_mut(&mut references.table, visitor)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Visit for OnConflict {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        let (set, filter) = match self {
+            OnConflict::OnConflict { action: ConflictAction::DoUpdate { set, filter }, .. } => {
+                (set, filter.as_ref())
+            }
+            OnConflict::DuplicateKeyUpdate(set) => (set, None),
+            _ => return,
+        };
+        set.visit(visitor);
+        if let Some(filter) = filter {
+            filter.visit(visitor);
+        }
+    }
+}
+
+impl Visit for Set {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        visitor.pre_visit_assignment(self);
+        self.expr.visit(visitor);
+        visitor.post_visit_assignment(self);
+    }
+}
+
+impl VisitMut for Set {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        visitor.pre_visit_assignment(self);
+        self.expr.visit_mut(visitor);
+        visitor.post_visit_assignment(self);
+    }
+}
+
+impl VisitMut for OnConflict {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        let (set, filter) = match self {
+            OnConflict::OnConflict { action: ConflictAction::DoUpdate { set, filter }, .. } => {
+                (set, filter.as_mut())
+            }
+            OnConflict::DuplicateKeyUpdate(set) => (set, None),
+            _ => return,
+        };
+        set.visit_mut(visitor);
+        if let Some(filter) = filter {
+            filter.visit_mut(visitor);
+        }
+    }
+}
+
+impl Visit for TableConstraint {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        match &self.kind {
+            TableConstraintKind::Check(expr) => expr.visit(visitor),
+            TableConstraintKind::ForeignKey { references, .. } => {
+                visit_table_name(&references.table, visitor)
+            }
+            _ => {}
+        }
+    }
+}
+
+impl VisitMut for TableConstraint {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        match &mut self.kind {
+            TableConstraintKind::Check(expr) => expr.visit_mut(visitor),
+            TableConstraintKind::ForeignKey { references, .. } => {
+                visit_table_name_mut(&mut references.table, visitor)
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Visit for Select {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        self.body.visit(visitor);
+        for OrderByExpr { expr, .. } in &self.order {
+            expr.visit(visitor);
+        }
+        self.limit.visit(visitor);
+        self.offset.visit(visitor);
+    }
+}
+
+impl VisitMut for Select {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        self.body.visit_mut(visitor);
+        for OrderByExpr { expr, .. } in &mut self.order {
+            expr.visit_mut(visitor);
+        }
+        self.limit.visit_mut(visitor);
+        self.offset.visit_mut(visitor);
+    }
+}
+
+impl Visit for Query {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        visitor.pre_visit_query(self);
+        self.projection.visit(visitor);
+        self.from.visit(visitor);
+        self.joins.visit(visitor);
+        self.filter.visit(visitor);
+        self.group.visit(visitor);
+        self.having.visit(visitor);
+        visitor.post_visit_query(self);
+    }
+}
+
+impl VisitMut for Query {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        visitor.pre_visit_query(self);
+        self.projection.visit_mut(visitor);
+        self.from.visit_mut(visitor);
+        self.joins.visit_mut(visitor);
+        self.filter.visit_mut(visitor);
+        self.group.visit_mut(visitor);
+        self.having.visit_mut(visitor);
+        visitor.post_visit_query(self);
+    }
+}
+
+impl Visit for SelectItem {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        visitor.pre_visit_select_item(self);
+        match &self.kind {
+            SelectItemKind::Expr(expr) | SelectItemKind::AliasedExpr { expr, .. } => {
+                expr.visit(visitor)
+            }
+            SelectItemKind::QualifiedWildcard(_) | SelectItemKind::Wildcard => {}
+        }
+        visitor.post_visit_select_item(self);
+    }
+}
+
+impl VisitMut for SelectItem {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        visitor.pre_visit_select_item(self);
+        match &mut self.kind {
+            SelectItemKind::Expr(expr) | SelectItemKind::AliasedExpr { expr, .. } => {
+                expr.visit_mut(visitor)
+            }
+            SelectItemKind::QualifiedWildcard(_) | SelectItemKind::Wildcard => {}
+        }
+        visitor.post_visit_select_item(self);
+    }
+}
+
+impl Visit for FromTable {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        visitor.pre_visit_from_table(self);
+        match &self.kind {
+            FromTableKind::Table { name, .. } => visit_table_name(name, visitor),
+            FromTableKind::Derived { query, .. } => query.visit(visitor),
+        }
+        visitor.post_visit_from_table(self);
+    }
+}
+
+impl VisitMut for FromTable {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        visitor.pre_visit_from_table(self);
+        match &mut self.kind {
+            FromTableKind::Table { name, .. } => visit_table_name_mut(name, visitor),
+            FromTableKind::Derived { query, .. } => query.visit_mut(visitor),
+        }
+        visitor.post_visit_from_table(self);
+    }
+}
+
+impl Visit for Join {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        visitor.pre_visit_join(self);
+        self.from.visit(visitor);
+        if let JoinConstraint::On(expr) = &self.constraint {
+            expr.visit(visitor);
+        }
+        visitor.post_visit_join(self);
+    }
+}
+
+impl VisitMut for Join {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        visitor.pre_visit_join(self);
+        self.from.visit_mut(visitor);
+        if let JoinConstraint::On(expr) = &mut self.constraint {
+            expr.visit_mut(visitor);
+        }
+        visitor.post_visit_join(self);
+    }
+}
+
+impl Visit for Expr {
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        visitor.pre_visit_expr(self);
+        match &self.kind {
+            ExprKind::Ident(_)
+            | ExprKind::Wildcard
+            | ExprKind::Default
+            | ExprKind::QualifiedWildcard(_)
+            | ExprKind::Value(_) => {}
+            ExprKind::IsNull(expr) | ExprKind::IsNotNull(expr) | ExprKind::UnaryOp { expr, .. } => {
+                expr.visit(visitor)
+            }
+            ExprKind::InList { expr, list, .. } => {
+                expr.visit(visitor);
+                list.visit(visitor);
+            }
+            ExprKind::Between { expr, low, high, .. } => {
+                expr.visit(visitor);
+                low.visit(visitor);
+                high.visit(visitor);
+            }
+            ExprKind::BinaryOp { left, right, .. } => {
+                left.visit(visitor);
+                right.visit(visitor);
+            }
+            ExprKind::SubQuery(query) => query.visit(visitor),
+            ExprKind::GroupingSets(sets) | ExprKind::Rollup(sets) | ExprKind::Cube(sets) => {
+                for set in sets {
+                    set.visit(visitor);
+                }
+            }
+            ExprKind::Function { args, filter, .. } => {
+                args.visit(visitor);
+                filter.visit(visitor);
+            }
+        }
+        visitor.post_visit_expr(self);
+    }
+}
+
+impl VisitMut for Expr {
+    fn visit_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        visitor.pre_visit_expr(self);
+        match &mut self.kind {
+            ExprKind::Ident(_)
+            | ExprKind::Wildcard
+            | ExprKind::Default
+            | ExprKind::QualifiedWildcard(_)
+            | ExprKind::Value(_) => {}
+            ExprKind::IsNull(expr) | ExprKind::IsNotNull(expr) | ExprKind::UnaryOp { expr, .. } => {
+                expr.visit_mut(visitor)
+            }
+            ExprKind::InList { expr, list, .. } => {
+                expr.visit_mut(visitor);
+                list.visit_mut(visitor);
+            }
+            ExprKind::Between { expr, low, high, .. } => {
+                expr.visit_mut(visitor);
+                low.visit_mut(visitor);
+                high.visit_mut(visitor);
+            }
+            ExprKind::BinaryOp { left, right, .. } => {
+                left.visit_mut(visitor);
+                right.visit_mut(visitor);
+            }
+            ExprKind::SubQuery(query) => query.visit_mut(visitor),
+            ExprKind::GroupingSets(sets) | ExprKind::Rollup(sets) | ExprKind::Cube(sets) => {
+                for set in sets {
+                    set.visit_mut(visitor);
+                }
+            }
+            ExprKind::Function { args, filter, .. } => {
+                args.visit_mut(visitor);
+                filter.visit_mut(visitor);
+            }
+        }
+        visitor.post_visit_expr(self);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ast::{Expr, ExprKind, Ident, Set, Statement},
+        dialect::GenericDialect,
+        parser::Parser,
+    };
+
+    use super::{Visit, VisitMut, Visitor, VisitorMut};
+
+    fn parse(input: &str) -> Vec<Statement> {
+        Parser::new(input, &GenericDialect).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_collect_tables() {
+        #[derive(Default)]
+        struct Tables(Vec<String>);
+
+        impl Visitor for Tables {
+            fn pre_visit_table_name(&mut self, name: &Ident) {
+                self.0.push(name.to_string());
+            }
+        }
+
+        let input = "
+            select * from t1 join s1.t2 on t1.a = (select max(a) from t3)
+            where b in (select b from (select b from t4) t5) order by (select 1 from t6);
+            create view v1 as select * from t7 limit (select 1 from t8);
+            create index i1 on t9 (a) where b > (select 1 from t10);
+            insert into a select * from t11 on conflict do update set x = (select 1 from t12);
+            update b set x = (select 1 from t13) returning (select 1 from t14);
+            delete from c where x in (select x from t15);
+            create index i2 on d (x);
+            create table e (x int references f, foreign key (x) references g (y));
+            alter table h add foreign key (x) references i, rename to x;
+            drop index i1; truncate j, k";
+
+        let mut tables = Tables::default();
+        parse(input).visit(&mut tables);
+        let want = [
+            "t1", "s1.t2", "t3", "t4", "t6", "v1", "t7", "t8", "t9", "t10", "a", "t11", "t12", "b",
+            "t13", "t14", "c", "t15", "d", "e", "f", "g", "h", "i", "x", "j", "k",
+        ];
+        assert_eq!(Vec::from(want.map(String::from)), tables.0);
+    }
+
+    #[test]
+    fn test_pre_and_post_order() {
+        #[derive(Default)]
+        struct Order(Vec<String>);
+
+        impl Visitor for Order {
+            fn pre_visit_expr(&mut self, expr: &Expr) {
+                self.0.push(format!("pre {expr}"));
+            }
+
+            fn post_visit_expr(&mut self, expr: &Expr) {
+                self.0.push(format!("post {expr}"));
+            }
+        }
+
+        let mut order = Order::default();
+        parse("select a + 1 from t1").visit(&mut order);
+        let want = ["pre a + 1", "pre a", "post a", "pre 1", "post 1", "post a + 1"];
+        assert_eq!(Vec::from(want.map(String::from)), order.0);
+    }
+
+    #[test]
+    fn test_qualify_idents() {
+        struct Qualify(&'static str);
+
+        impl VisitorMut for Qualify {
+            fn post_visit_expr(&mut self, expr: &mut Expr) {
+                if let ExprKind::Ident(Ident::Single(name)) = &mut expr.kind {
+                    let name = std::mem::take(name);
+                    expr.kind = ExprKind::Ident(Ident::Compound(vec![self.0.into(), name]));
+                }
+            }
+        }
+
+        let mut have = parse("select a, count(b) from t1 where c in (1, d) group by e");
+        have.visit_mut(&mut Qualify("t1"));
+
+        let want = parse("select t1.a, count(t1.b) from t1 where t1.c in (1, t1.d) group by t1.e");
+        assert_eq!(want, have);
+    }
+
+    #[test]
+    fn test_rename_tables_and_assignments() {
+        struct Rename;
+
+        impl VisitorMut for Rename {
+            fn pre_visit_table_name(&mut self, name: &mut Ident) {
+                if let Ident::Single(table) = name {
+                    *name = Ident::Compound(vec!["s1".into(), std::mem::take(table)]);
+                }
+            }
+
+            fn pre_visit_assignment(&mut self, set: &mut Set) {
+                set.column = Ident::Single(format!("new_{}", set.column).into());
+            }
+        }
+
+        let mut have = parse(
+            "update t1 set a = 1, b = (select b from t2) where c = 1; \
+             insert into t3 values (1) on duplicate key update a = 2",
+        );
+        have.visit_mut(&mut Rename);
+
+        let want = parse(
+            "update s1.t1 set new_a = 1, new_b = (select b from s1.t2) where c = 1; \
+             insert into s1.t3 values (1) on duplicate key update new_a = 2",
+        );
+        assert_eq!(want, have);
+    }
+}